@@ -0,0 +1,191 @@
+use bytes::Bytes;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use ftx_rest_client::{
+    endpoints::{
+        futures::GetFuturesResponse,
+        markets::{GetMarketsResponse, GetOrderBookResponse},
+    },
+    Response,
+};
+
+const NUM_FUTURES: usize = 250;
+const NUM_MARKETS: usize = 1000;
+const BOOK_DEPTH: usize = 100;
+
+fn futures_body() -> Bytes {
+    let futures: Vec<String> = (0..NUM_FUTURES)
+        .map(|i| {
+            format!(
+                r#"{{
+      "name": "COIN{i}-PERP",
+      "underlying": "COIN{i}",
+      "description": "Coin {i} Perpetual Futures",
+      "type": "perpetual",
+      "expiry": null,
+      "perpetual": true,
+      "expired": false,
+      "enabled": true,
+      "postOnly": false,
+      "closeOnly": false,
+      "priceIncrement": 0.5,
+      "sizeIncrement": 0.0001,
+      "last": 4620{i}.5,
+      "bid": 4620{i}.0,
+      "ask": 4620{i}.5,
+      "index": 46088.731248179,
+      "mark": 4620{i}.5,
+      "imfFactor": 0.002,
+      "imfWeight": 1,
+      "mmfWeight": 1,
+      "lowerBound": 43786.5,
+      "upperBound": 48394.5,
+      "underlyingDescription": "Coin {i}",
+      "expiryDescription": "Perpetual",
+      "moveStart": null,
+      "marginPrice": 46203.5,
+      "positionLimitWeight": 1,
+      "group": "perpetual",
+      "change1h": 0.0012155295090227513,
+      "change24h": -0.012209302325581395,
+      "changeBod": -0.0035587188612099642,
+      "volumeUsd24h": 2389473120.8367,
+      "volume": 51562.6702,
+      "openInterest": 36241.0466,
+      "openInterestUsd": 1674468567.5
+    }}"#,
+                i = i
+            )
+        })
+        .collect();
+
+    format!(r#"{{"success": true, "result": [{}]}}"#, futures.join(",")).into()
+}
+
+fn markets_body() -> Bytes {
+    let markets: Vec<String> = (0..NUM_MARKETS)
+        .map(|i| {
+            format!(
+                r#"{{
+      "name": "COIN{i}-PERP",
+      "baseCurrency": null,
+      "quoteCurrency": null,
+      "quoteVolume24h": 28914.76,
+      "change1h": 0.012,
+      "change24h": 0.0299,
+      "changeBod": 0.0156,
+      "highLeverageFeeExempt": false,
+      "minProvideSize": 0.001,
+      "type": "future",
+      "underlying": "COIN{i}",
+      "enabled": true,
+      "ask": 3949.25,
+      "bid": 3949,
+      "last": 10579.52,
+      "postOnly": false,
+      "price": 10579.52,
+      "priceIncrement": 0.25,
+      "sizeIncrement": 0.0001,
+      "restricted": false,
+      "volumeUsd24h": 28914.76,
+      "largeOrderThreshold": 5000.0,
+      "isEtfMarket": false
+    }}"#,
+                i = i
+            )
+        })
+        .collect();
+
+    format!(r#"{{"success": true, "result": [{}]}}"#, markets.join(",")).into()
+}
+
+fn orderbook_body() -> Bytes {
+    let levels = |start: f64, step: f64| -> String {
+        (0..BOOK_DEPTH)
+            .map(|i| format!("[{}, {}]", start + step * i as f64, 0.0123 * (i + 1) as f64))
+            .collect::<Vec<_>>()
+            .join(",")
+    };
+
+    format!(
+        r#"{{"success": true, "result": {{"asks": [{}], "bids": [{}]}}}}"#,
+        levels(46204.0, 0.5),
+        levels(46203.5, -0.5)
+    )
+    .into()
+}
+
+fn get_futures(c: &mut Criterion) {
+    let response = GetFuturesResponse::from(futures_body());
+
+    let mut group = c.benchmark_group("get_futures");
+
+    group.bench_function("deserialize", |b| {
+        b.iter(|| black_box(&response).deserialize().unwrap())
+    });
+
+    group.bench_function("deserialize_partial", |b| {
+        b.iter(|| black_box(&response).deserialize_partial().unwrap())
+    });
+
+    group.bench_function("deserialize_partial_then_mark", |b| {
+        b.iter(|| {
+            black_box(&response)
+                .deserialize_partial()
+                .unwrap()
+                .into_iter()
+                .map(|f| (f.name, f.mark.deserialize().unwrap()))
+                .collect::<Vec<_>>()
+        })
+    });
+
+    group.finish();
+}
+
+// Parsing is on `serde_json` only, see `FtxResponse::try_from`, so this
+// is a baseline for the largest common response rather than a comparison
+// between backends.
+fn get_markets(c: &mut Criterion) {
+    let response = GetMarketsResponse::from(markets_body());
+
+    let mut group = c.benchmark_group("get_markets");
+
+    group.bench_function("deserialize", |b| {
+        b.iter(|| black_box(&response).deserialize().unwrap())
+    });
+
+    group.bench_function("deserialize_partial", |b| {
+        b.iter(|| black_box(&response).deserialize_partial().unwrap())
+    });
+
+    group.finish();
+}
+
+fn get_orderbook(c: &mut Criterion) {
+    let response = GetOrderBookResponse::from(orderbook_body());
+
+    let mut group = c.benchmark_group("get_orderbook");
+
+    group.bench_function("deserialize", |b| {
+        b.iter(|| black_box(&response).deserialize().unwrap())
+    });
+
+    group.bench_function("deserialize_partial", |b| {
+        b.iter(|| black_box(&response).deserialize_partial().unwrap())
+    });
+
+    group.bench_function("deserialize_partial_then_top_of_book", |b| {
+        b.iter(|| {
+            let book = black_box(&response).deserialize_partial().unwrap();
+
+            let best_ask = book.asks.first().map(|(p, _)| p.deserialize().unwrap());
+            let best_bid = book.bids.first().map(|(p, _)| p.deserialize().unwrap());
+
+            (best_ask, best_bid)
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, get_futures, get_markets, get_orderbook);
+criterion_main!(benches);
@@ -0,0 +1,35 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use ftx_rest_client::{
+    data::{SortOrder, UnixTimestamp},
+    endpoints::fills::GetFills,
+    QueryWriter, Request,
+};
+
+fn get_fills(c: &mut Criterion) {
+    let request = GetFills {
+        market: Some("BTC/USD"),
+        start_time: Some(UnixTimestamp::new(1648996980000)),
+        end_time: Some(UnixTimestamp::new(1648999860000)),
+        order_id: Some(8436981),
+        order: Some(SortOrder::Ascending),
+    };
+
+    let mut group = c.benchmark_group("get_fills_query");
+
+    group.bench_function("query_params", |b| {
+        b.iter(|| serde_urlencoded::to_string(black_box(&request).query_params()).unwrap())
+    });
+
+    group.bench_function("write_query", |b| {
+        b.iter(|| {
+            let mut query = String::with_capacity(128);
+            black_box(&request).write_query(&mut QueryWriter::new(&mut query));
+            query
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, get_fills);
+criterion_main!(benches);
@@ -7,9 +7,7 @@ async fn main() {
 
     let response = client
         .execute(
-            &GetMarket {
-                market: &"BTC-PERP",
-            },
+            &GetMarket { market: "BTC-PERP" },
             Some(Duration::from_secs(10)),
         )
         .await
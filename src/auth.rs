@@ -1,7 +1,11 @@
 use hmac::{digest::InvalidLength, Hmac, Mac};
-use reqwest::{header::HeaderMap, Method};
+use http::{header::HeaderMap, Method};
 use sha2::Sha256;
-use std::convert::{TryFrom, TryInto};
+use std::{
+    convert::{TryFrom, TryInto},
+    fmt,
+    sync::Arc,
+};
 use urlencoding;
 
 use crate::{
@@ -9,10 +13,13 @@ use crate::{
     error::{Error, ErrorKind},
 };
 
-const FTX_KEY_HEADER: &'static str = "FTX-KEY";
-const FTX_SIGN_HEADER: &'static str = "FTX-SIGN";
-const FTX_TS_HEADER: &'static str = "FTX-TS";
-const FTX_SUBACCOUNT_HEADER: &'static str = "FTX-SUBACCOUNT";
+const FTX_KEY_HEADER: &str = "FTX-KEY";
+const FTX_SIGN_HEADER: &str = "FTX-SIGN";
+const FTX_TS_HEADER: &str = "FTX-TS";
+const FTX_SUBACCOUNT_HEADER: &str = "FTX-SUBACCOUNT";
+
+/// Path the FTX API is served under, which is included in the signature.
+pub(crate) const DEFAULT_API_PREFIX: &str = "/api";
 
 #[derive(Clone)]
 pub struct PrivateKey(String);
@@ -29,6 +36,13 @@ impl<T: Into<String>> From<T> for PrivateKey {
     }
 }
 
+// Never print the secret.
+impl fmt::Debug for PrivateKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("PrivateKey(***)")
+    }
+}
+
 impl TryFrom<PrivateKey> for Hmac<Sha256> {
     type Error = InvalidLength;
 
@@ -67,9 +81,19 @@ impl<T: Into<String>> From<T> for Subaccount {
     }
 }
 
+/// Computes the hex encoded HMAC-SHA256 of the string passed to it,
+/// keyed with an API private key held outside the crate.
+pub type Signer = Arc<dyn Fn(&str) -> String + Send + Sync>;
+
+#[derive(Clone)]
+enum SigningKey {
+    Hmac(Hmac<Sha256>),
+    External(Signer),
+}
+
 #[derive(Clone)]
 pub struct Authenticator {
-    hmac: Hmac<Sha256>,
+    key: SigningKey,
     base_headers: HeaderMap,
 }
 
@@ -81,6 +105,26 @@ impl Authenticator {
     ) -> Result<Self, Error> {
         let hmac = private_key.try_into()?;
 
+        Self::with_key(public_key, subaccount, SigningKey::Hmac(hmac))
+    }
+
+    /// Authenticate with a private key that isn't available to the
+    /// crate, e.g. because it's held in an HSM. `signer` is passed the
+    /// string to sign for each request and must return its hex encoded
+    /// HMAC-SHA256.
+    pub fn with_signer(
+        public_key: PublicKey,
+        subaccount: Option<Subaccount>,
+        signer: Signer,
+    ) -> Result<Self, Error> {
+        Self::with_key(public_key, subaccount, SigningKey::External(signer))
+    }
+
+    fn with_key(
+        public_key: PublicKey,
+        subaccount: Option<Subaccount>,
+        key: SigningKey,
+    ) -> Result<Self, Error> {
         let mut base_headers = HeaderMap::with_capacity(2);
 
         add_header_value(FTX_KEY_HEADER, &public_key.0, &mut base_headers)?;
@@ -93,17 +137,57 @@ impl Authenticator {
             )?;
         }
 
-        Ok(Self { hmac, base_headers })
+        Ok(Self { key, base_headers })
+    }
+
+    /// Whether requests are made on behalf of a subaccount.
+    #[cfg_attr(not(feature = "client"), allow(dead_code))]
+    pub(crate) fn has_subaccount(&self) -> bool {
+        self.base_headers.contains_key(FTX_SUBACCOUNT_HEADER)
+    }
+
+    /// Generate the headers needed to authenticate a request, signed
+    /// with the current time. `path` is relative to the API root and
+    /// must include any query string, e.g. `/orders?market=BTC-PERP`.
+    pub fn auth_headers(
+        &self,
+        method: &Method,
+        path: &str,
+        body: Option<&str>,
+    ) -> Result<HeaderMap, Error> {
+        self.auth_headers_at(UnixTimestamp::from_system_time(), method, path, body)
+    }
+
+    /// As [`auth_headers`](Self::auth_headers), but signed with the
+    /// given timestamp.
+    pub fn auth_headers_at(
+        &self,
+        timestamp: UnixTimestamp,
+        method: &Method,
+        path: &str,
+        body: Option<&str>,
+    ) -> Result<HeaderMap, Error> {
+        self.auth_headers_under_prefix_at(timestamp, method, DEFAULT_API_PREFIX, path, body)
     }
 
-    pub(crate) fn generate_auth_headers(
+    /// As [`auth_headers_at`](Self::auth_headers_at), for an API served
+    /// under `api_prefix` rather than `/api`.
+    pub(crate) fn auth_headers_under_prefix_at(
         &self,
         timestamp: UnixTimestamp,
         method: &Method,
+        api_prefix: &str,
         path: &str,
-        payload: Option<&str>,
+        body: Option<&str>,
     ) -> Result<HeaderMap, Error> {
-        let signature = sign(self.hmac.clone(), timestamp, method, path, payload)?;
+        let signature = match &self.key {
+            SigningKey::Hmac(hmac) => {
+                sign(hmac.clone(), timestamp, method, api_prefix, path, body)?
+            }
+            SigningKey::External(signer) => {
+                signer(&to_sign(timestamp, method, api_prefix, path, body))
+            }
+        };
 
         let mut headers = self.base_headers.clone();
 
@@ -114,19 +198,42 @@ impl Authenticator {
     }
 }
 
+// The HMAC state is derived from the private key, so it's left out.
+impl fmt::Debug for Authenticator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Authenticator").finish_non_exhaustive()
+    }
+}
+
 fn sign(
     mut hmac: Hmac<Sha256>,
     timestamp: UnixTimestamp,
     method: &Method,
+    api_prefix: &str,
     path: &str,
     payload: Option<&str>,
 ) -> Result<String, Error> {
-    let body = payload.unwrap_or("");
+    hmac.update(to_sign(timestamp, method, api_prefix, path, payload).as_bytes());
+    Ok(hex::encode(hmac.finalize().into_bytes()))
+}
 
-    let to_sign = format!("{}{}/api{}{}", timestamp.get(), method, path, body);
+fn to_sign(
+    timestamp: UnixTimestamp,
+    method: &Method,
+    api_prefix: &str,
+    path: &str,
+    payload: Option<&str>,
+) -> String {
+    let body = payload.unwrap_or("");
 
-    hmac.update(to_sign.as_bytes());
-    Ok(hex::encode(&hmac.finalize().into_bytes()))
+    format!(
+        "{}{}{}{}{}",
+        timestamp.get(),
+        method,
+        api_prefix,
+        path,
+        body
+    )
 }
 
 fn add_header_value(
@@ -148,6 +255,115 @@ fn add_header_value(
 mod tests {
     use super::*;
 
+    #[test]
+    fn auth_headers_should_be_present() {
+        let authenticator = Authenticator::new(
+            PublicKey::new("public"),
+            PrivateKey::new("YAGN-Np3au9igIMqIAPiJTF1zy9heo55_FNfYEru"),
+            None,
+        )
+        .unwrap();
+
+        let headers = authenticator
+            .auth_headers(&Method::GET, "/spot_margin/borrow_rates", None)
+            .unwrap();
+
+        assert_eq!(headers[FTX_KEY_HEADER], "public");
+        assert!(headers.contains_key(FTX_SIGN_HEADER));
+        assert!(headers.contains_key(FTX_TS_HEADER));
+        assert!(!headers.contains_key(FTX_SUBACCOUNT_HEADER));
+    }
+
+    #[test]
+    fn auth_headers_at_should_match_known_signatures() {
+        let authenticator = |private_key| {
+            Authenticator::new(PublicKey::new("public"), PrivateKey::new(private_key), None)
+                .unwrap()
+        };
+
+        let headers = authenticator("YAGN-Np3au9igIMqIAPiJTF1zy9heo55_FNfYEru")
+            .auth_headers_at(
+                UnixTimestamp::new(1617659558822),
+                &Method::GET,
+                "/spot_margin/borrow_rates",
+                None,
+            )
+            .unwrap();
+
+        assert_eq!(
+            headers[FTX_SIGN_HEADER],
+            "421c580094ab840e832071c75602f1f7d1504901175589284e6ce81ff163ec0b"
+        );
+        assert_eq!(headers[FTX_TS_HEADER], "1617659558822");
+
+        let request_body = r#"{"market": "BTC-PERP", "side": "buy", "price": 8500, "size": 1, "type": "limit", "reduceOnly": false, "ioc": false, "postOnly": false, "clientId": null}"#;
+
+        let headers = authenticator("T4lPid48QtjNxjLUFOcUZghD7CUJ7sTVsfuvQZF2")
+            .auth_headers_at(
+                UnixTimestamp::new(1588591856950),
+                &Method::POST,
+                "/orders",
+                Some(request_body),
+            )
+            .unwrap();
+
+        assert_eq!(
+            headers[FTX_SIGN_HEADER],
+            "c4fbabaf178658a59d7bbf57678d44c369382f3da29138f04cd46d3d582ba4ba"
+        );
+        assert_eq!(headers[FTX_TS_HEADER], "1588591856950");
+    }
+
+    #[test]
+    fn external_signer_should_match_known_signature() {
+        let signer: Signer = Arc::new(|to_sign: &str| {
+            let private_key = PrivateKey::new("YAGN-Np3au9igIMqIAPiJTF1zy9heo55_FNfYEru");
+            let mut hmac: Hmac<Sha256> = private_key.try_into().unwrap();
+
+            hmac.update(to_sign.as_bytes());
+            hex::encode(hmac.finalize().into_bytes())
+        });
+
+        let authenticator =
+            Authenticator::with_signer(PublicKey::new("public"), None, signer).unwrap();
+
+        let headers = authenticator
+            .auth_headers_at(
+                UnixTimestamp::new(1617659558822),
+                &Method::GET,
+                "/spot_margin/borrow_rates",
+                None,
+            )
+            .unwrap();
+
+        assert_eq!(headers[FTX_KEY_HEADER], "public");
+        assert_eq!(
+            headers[FTX_SIGN_HEADER],
+            "421c580094ab840e832071c75602f1f7d1504901175589284e6ce81ff163ec0b"
+        );
+        assert_eq!(headers[FTX_TS_HEADER], "1617659558822");
+    }
+
+    #[test]
+    fn debug_output_should_not_contain_secrets() {
+        let secret = "YAGN-Np3au9igIMqIAPiJTF1zy9heo55_FNfYEru";
+
+        let private_key = PrivateKey::new(secret);
+        assert_eq!(format!("{:?}", private_key), "PrivateKey(***)");
+
+        let authenticator = Authenticator::new(
+            PublicKey::new("public"),
+            private_key,
+            Some(Subaccount::new("sub")),
+        )
+        .unwrap();
+
+        let debug = format!("{:?}", authenticator);
+        assert_eq!(debug, "Authenticator { .. }");
+        assert!(!debug.contains(secret));
+        assert!(!format!("{:#?}", authenticator).contains(secret));
+    }
+
     #[test]
     fn signature_for_get_request_should_be_correct() {
         let private_key = PrivateKey::new("YAGN-Np3au9igIMqIAPiJTF1zy9heo55_FNfYEru");
@@ -158,6 +374,7 @@ mod tests {
             private_key.try_into().unwrap(),
             timestamp,
             &Method::GET,
+            DEFAULT_API_PREFIX,
             path,
             None,
         )
@@ -181,8 +398,9 @@ mod tests {
             private_key.try_into().unwrap(),
             timestamp,
             &Method::POST,
+            DEFAULT_API_PREFIX,
             path,
-            Some(&request_body),
+            Some(request_body),
         )
         .unwrap();
 
@@ -1,23 +1,350 @@
 use async_trait::async_trait;
-use bytes::Bytes;
-use reqwest::header::{HeaderValue, CONTENT_TYPE};
-use std::{borrow::Cow, convert::TryInto, error::Error as StdError, fmt, time::Duration};
-use time::OffsetDateTime;
+use bytes::{Bytes, BytesMut};
+use futures_util::future::{join_all, select, Either};
+use reqwest::{
+    header::{HeaderMap, HeaderValue, CONTENT_TYPE, RETRY_AFTER},
+    Method, StatusCode,
+};
+use serde::de::IgnoredAny;
+use std::{
+    borrow::Cow,
+    collections::{hash_map::RandomState, HashMap},
+    convert::{TryFrom, TryInto},
+    future::Future,
+    hash::{BuildHasher, Hasher},
+    num::NonZeroU32,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use time::{format_description::well_known::Rfc2822, OffsetDateTime};
 
 use crate::{
-    auth::Authenticator,
-    error::{BoxError, Error, ErrorKind},
-    AuthExecutor, Executor, QueryParams, Request,
+    auth::{Authenticator, DEFAULT_API_PREFIX},
+    data::{PositiveDecimal, Side, UnixTimestamp},
+    endpoints::{
+        fills::{page_fills, FillHistory},
+        futures::{GetFutureStats, GetFutureStatsResponse},
+        markets::GetMarket,
+        orders::{
+            orders_created_before, CancelOrder, GetOpenOrders, GetOrderStatus,
+            GetOrderStatusResponse, OrderId, OrderOpts, PlaceOrder, PlaceOrderResponse,
+        },
+        raw::{RawRequest, RawResponse},
+        subaccounts::{
+            GetSubaccountBalances, GetSubaccountBalancesResponse, GetSubaccounts,
+            TransferBetweenSubaccounts, TransferBetweenSubaccountsResponse,
+        },
+        wallet::GetBalances,
+        FtxResponse,
+    },
+    error::{Error, ErrorKind, RejectionReason},
+    AuthExecutor, Executor, QueryWriter, Request, Response,
 };
 
-const BASE_URL: &str = "https://ftx.com/api";
+const DEFAULT_HOST: &str = "https://ftx.com";
+
+const DEFAULT_MAX_URL_LEN: usize = 8 * 1024;
+
+#[derive(Debug, Clone, Copy)]
+struct Limits {
+    max_url_len: usize,
+    max_response_bytes: Option<usize>,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_url_len: DEFAULT_MAX_URL_LEN,
+            max_response_bytes: None,
+        }
+    }
+}
+
+/// Where requests are sent, split into the host, e.g. `https://ftx.com`,
+/// and the path the API is served under, e.g. `/api`, which is included
+/// in the signature of authenticated requests.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct BaseUrl {
+    host: Arc<str>,
+    api_prefix: Arc<str>,
+}
+
+impl Default for BaseUrl {
+    fn default() -> Self {
+        Self {
+            host: DEFAULT_HOST.into(),
+            api_prefix: DEFAULT_API_PREFIX.into(),
+        }
+    }
+}
+
+impl BaseUrl {
+    fn parse(url: &str) -> Result<Self, Error> {
+        let invalid = |msg: &'static str| Error::new(ErrorKind::InvalidUrl).with_source(msg);
+
+        let url = reqwest::Url::parse(url)
+            .map_err(|e| Error::new(ErrorKind::InvalidUrl).with_source(e))?;
+
+        if !matches!(url.scheme(), "http" | "https") || !url.has_host() {
+            return Err(invalid("base URL must be an http or https URL"));
+        }
+        if url.query().is_some() || url.fragment().is_some() {
+            return Err(invalid("base URL must not have a query string or fragment"));
+        }
+
+        Ok(Self {
+            host: url.origin().ascii_serialization().into(),
+            api_prefix: url.path().trim_end_matches('/').into(),
+        })
+    }
+
+    fn len(&self) -> usize {
+        self.host.len() + self.api_prefix.len()
+    }
+}
+
+/// How failed requests are retried, see [`Client::with_retry_policy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Number of retries after the first attempt.
+    pub max_retries: u32,
+    /// Delay before the first retry, doubling for each retry after.
+    pub initial_backoff: Duration,
+    /// Longest delay between attempts. A request the exchange asks to
+    /// wait longer before retrying fails instead.
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(250),
+            max_backoff: Duration::from_secs(10),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before retry number `retry`, counting from zero. `jitter`
+    /// is in `[0, 1)` and shortens the delay by up to half, so clients
+    /// limited at the same time don't all retry together.
+    fn backoff(&self, retry: u32, jitter: f64) -> Duration {
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_backoff);
+
+        backoff.mul_f64(1.0 - jitter / 2.0)
+    }
+}
+
+/// Options for the connections made by the underlying HTTP client. `None`
+/// keeps `reqwest`'s default.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct ConnectionOptions {
+    http2_prior_knowledge: bool,
+    pool_idle_timeout: Option<Duration>,
+    tcp_keepalive: Option<Duration>,
+}
+
+/// Source of the current time used to sign requests.
+pub trait Clock: Send + Sync {
+    fn now_utc(&self) -> OffsetDateTime;
+}
+
+/// The system clock, used by default.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_utc(&self) -> OffsetDateTime {
+        OffsetDateTime::now_utc()
+    }
+}
+
+/// A clock stuck at a fixed time, e.g. to produce deterministic
+/// signatures in tests.
+#[derive(Debug, Clone, Copy)]
+pub struct FixedClock(pub OffsetDateTime);
+
+impl Clock for FixedClock {
+    fn now_utc(&self) -> OffsetDateTime {
+        self.0
+    }
+}
+
+/// Hooks called around every request a client sends, e.g. to export
+/// metrics. These run inline on the request path so should return
+/// quickly.
+pub trait RequestObserver: Send + Sync {
+    /// Called just before a request is sent. `path` excludes the query
+    /// string.
+    fn on_request(&self, _method: &Method, _path: &str) {}
+
+    /// Called once the response body has been read or the request has
+    /// failed. `status` is `None` if no response was received. `latency`
+    /// runs from just before the request is sent until the body has been
+    /// read, so includes the exchange's processing time.
+    fn on_response(
+        &self,
+        _method: &Method,
+        _path: &str,
+        _status: Option<StatusCode>,
+        _latency: Duration,
+    ) {
+    }
+}
 
 #[derive(Clone)]
-pub struct Client(reqwest::Client);
+pub struct Client {
+    executor: reqwest::Client,
+    custom_executor: bool,
+    base_url: BaseUrl,
+    connection: ConnectionOptions,
+    observer: Option<Arc<dyn RequestObserver>>,
+    retry_policy: Option<RetryPolicy>,
+    limits: Limits,
+}
+
+impl Default for Client {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl Client {
     pub fn new() -> Self {
-        Self(reqwest::Client::new())
+        Self {
+            custom_executor: false,
+            ..Self::with_http_client(new_executor(ConnectionOptions::default()))
+        }
+    }
+
+    /// Send requests with an already configured `reqwest` client, e.g.
+    /// one shared with the rest of an application or with custom root
+    /// certificates. Connection options must then be set on that client,
+    /// the crate's connection setters, e.g.
+    /// [`with_tcp_keepalive`](Self::with_tcp_keepalive), fail.
+    pub fn with_http_client(executor: reqwest::Client) -> Self {
+        Self {
+            executor,
+            custom_executor: true,
+            base_url: BaseUrl::default(),
+            connection: ConnectionOptions::default(),
+            observer: None,
+            retry_policy: None,
+            limits: Limits::default(),
+        }
+    }
+
+    pub fn with_observer(mut self, observer: impl RequestObserver + 'static) -> Self {
+        self.observer = Some(Arc::new(observer));
+        self
+    }
+
+    /// Send requests to `url` rather than `https://ftx.com/api`, e.g. to
+    /// target FTX US or a mock server. Any path, e.g. `/api`, is taken
+    /// to be where the API is served and is included when signing
+    /// requests.
+    pub fn with_base_url(mut self, url: impl Into<String>) -> Result<Self, Error> {
+        self.base_url = BaseUrl::parse(&url.into())?;
+        Ok(self)
+    }
+
+    /// Retry requests rejected for exceeding rate limits or which the
+    /// exchange asked to be retried, waiting as long as the exchange asks,
+    /// up to [`RetryPolicy::max_backoff`], or backing off exponentially if
+    /// it doesn't say. If the exchange still asks for a retry once they
+    /// run out the request fails with [`ErrorKind::RetryRequested`].
+    /// Idempotent requests, see [`Request::is_idempotent`], are also
+    /// retried after timeouts, dropped connections and server errors.
+    /// Retries stop once a request's timeout would be exceeded. Off by
+    /// default.
+    ///
+    /// Whether or not a policy is set, a rate limited request fails with
+    /// [`ErrorKind::RateLimitExceeded`], keeping any message the exchange
+    /// gave in [`Error::exchange_message`].
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        self
+    }
+
+    /// Fail requests whose response body exceeds `limit` bytes, rather
+    /// than reading it all into memory. Unbounded by default.
+    pub fn with_max_response_bytes(mut self, limit: usize) -> Self {
+        self.limits.max_response_bytes = Some(limit);
+        self
+    }
+
+    /// Fail requests whose URL, including the query string, is longer
+    /// than `limit` bytes before sending them. Defaults to 8KiB.
+    pub fn with_max_url_len(mut self, limit: usize) -> Self {
+        self.limits.max_url_len = limit;
+        self
+    }
+
+    /// Only use HTTP/2, skipping the protocol negotiation on new
+    /// connections.
+    /// Fails with [`ErrorKind::RequestBuildFailed`] if the client was
+    /// created with [`with_http_client`](Self::with_http_client).
+    pub fn with_http2_prior_knowledge(mut self, enabled: bool) -> Result<Self, Error> {
+        self.connection.http2_prior_knowledge = enabled;
+        self.executor = rebuild_executor(self.custom_executor, self.connection)?;
+        Ok(self)
+    }
+
+    /// How long an idle connection is kept in the pool for reuse.
+    /// Defaults to 90 seconds.
+    /// Fails with [`ErrorKind::RequestBuildFailed`] if the client was
+    /// created with [`with_http_client`](Self::with_http_client).
+    pub fn with_pool_idle_timeout(mut self, timeout: Duration) -> Result<Self, Error> {
+        self.connection.pool_idle_timeout = Some(timeout);
+        self.executor = rebuild_executor(self.custom_executor, self.connection)?;
+        Ok(self)
+    }
+
+    /// Enable TCP keep-alive on connections, probing after `interval`
+    /// of inactivity. Disabled by default.
+    /// Fails with [`ErrorKind::RequestBuildFailed`] if the client was
+    /// created with [`with_http_client`](Self::with_http_client).
+    pub fn with_tcp_keepalive(mut self, interval: Duration) -> Result<Self, Error> {
+        self.connection.tcp_keepalive = Some(interval);
+        self.executor = rebuild_executor(self.custom_executor, self.connection)?;
+        Ok(self)
+    }
+}
+
+impl Client {
+    /// Execute a request against an endpoint not otherwise modelled by
+    /// the crate. Fails if `request.auth` is set, use an [`AuthClient`]
+    /// for those.
+    pub async fn execute_raw(
+        &self,
+        request: &RawRequest<'_>,
+        timeout: Option<Duration>,
+    ) -> Result<RawResponse, Error> {
+        if request.auth {
+            return Err(Error::new(ErrorKind::RequestBuildFailed)
+                .with_source("authenticated requests require an `AuthClient`"));
+        }
+
+        self.execute(request, timeout).await
+    }
+
+    /// Execute a request, abandoning it as soon as `cancel` completes,
+    /// e.g. on shutdown, rather than waiting for it to finish or time
+    /// out. Fails with [`ErrorKind::Cancelled`] if it's abandoned.
+    pub async fn execute_with_cancel<R>(
+        &self,
+        request: &R,
+        timeout: Option<Duration>,
+        cancel: impl Future,
+    ) -> Result<R::Response, Error>
+    where
+        R: Request<false> + Send + Sync,
+    {
+        cancellable(self.execute(request, timeout), cancel).await
     }
 }
 
@@ -27,23 +354,415 @@ where
     R: Request<false> + Send + Sync,
 {
     async fn execute(&self, request: &R, timeout: Option<Duration>) -> Result<R::Response, Error> {
-        build_and_execute_request(request, timeout, &self.0, None).await
+        build_and_execute_request(
+            request,
+            timeout,
+            &self.executor,
+            &self.base_url,
+            None,
+            self.observer.as_deref(),
+            self.retry_policy,
+            self.limits,
+        )
+        .await
     }
 }
 
 #[derive(Clone)]
 pub struct AuthClient {
     authenticator: Authenticator,
+    clock: Arc<dyn Clock>,
     executor: reqwest::Client,
+    custom_executor: bool,
+    base_url: BaseUrl,
+    connection: ConnectionOptions,
+    observer: Option<Arc<dyn RequestObserver>>,
+    retry_policy: Option<RetryPolicy>,
+    limits: Limits,
 }
 
 impl AuthClient {
     pub fn new(authenticator: Authenticator) -> Self {
+        Self {
+            custom_executor: false,
+            ..Self::with_http_client(authenticator, new_executor(ConnectionOptions::default()))
+        }
+    }
+
+    /// Send requests with an already configured `reqwest` client, e.g.
+    /// one shared with the rest of an application or with custom root
+    /// certificates. Connection options must then be set on that client,
+    /// the crate's connection setters, e.g.
+    /// [`with_tcp_keepalive`](Self::with_tcp_keepalive), fail.
+    pub fn with_http_client(authenticator: Authenticator, executor: reqwest::Client) -> Self {
         Self {
             authenticator,
-            executor: reqwest::Client::new(),
+            clock: Arc::new(SystemClock),
+            executor,
+            custom_executor: true,
+            base_url: BaseUrl::default(),
+            connection: ConnectionOptions::default(),
+            observer: None,
+            retry_policy: None,
+            limits: Limits::default(),
         }
     }
+
+    pub fn with_observer(mut self, observer: impl RequestObserver + 'static) -> Self {
+        self.observer = Some(Arc::new(observer));
+        self
+    }
+
+    /// Send requests to `url` rather than `https://ftx.com/api`, e.g. to
+    /// target FTX US or a mock server. Any path, e.g. `/api`, is taken
+    /// to be where the API is served and is included when signing
+    /// requests.
+    pub fn with_base_url(mut self, url: impl Into<String>) -> Result<Self, Error> {
+        self.base_url = BaseUrl::parse(&url.into())?;
+        Ok(self)
+    }
+
+    /// Take the time used to sign requests from `clock` rather than the
+    /// system clock.
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// Retry requests rejected for exceeding rate limits or which the
+    /// exchange asked to be retried, waiting as long as the exchange asks,
+    /// up to [`RetryPolicy::max_backoff`], or backing off exponentially if
+    /// it doesn't say. If the exchange still asks for a retry once they
+    /// run out the request fails with [`ErrorKind::RetryRequested`].
+    /// Idempotent requests, see [`Request::is_idempotent`], are also
+    /// retried after timeouts, dropped connections and server errors.
+    /// Retries stop once a request's timeout would be exceeded. Off by
+    /// default.
+    ///
+    /// Whether or not a policy is set, a rate limited request fails with
+    /// [`ErrorKind::RateLimitExceeded`], keeping any message the exchange
+    /// gave in [`Error::exchange_message`].
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        self
+    }
+
+    /// Fail requests whose response body exceeds `limit` bytes, rather
+    /// than reading it all into memory. Unbounded by default.
+    pub fn with_max_response_bytes(mut self, limit: usize) -> Self {
+        self.limits.max_response_bytes = Some(limit);
+        self
+    }
+
+    /// Fail requests whose URL, including the query string, is longer
+    /// than `limit` bytes before sending them. Defaults to 8KiB.
+    pub fn with_max_url_len(mut self, limit: usize) -> Self {
+        self.limits.max_url_len = limit;
+        self
+    }
+
+    /// Only use HTTP/2, skipping the protocol negotiation on new
+    /// connections.
+    /// Fails with [`ErrorKind::RequestBuildFailed`] if the client was
+    /// created with [`with_http_client`](Self::with_http_client).
+    pub fn with_http2_prior_knowledge(mut self, enabled: bool) -> Result<Self, Error> {
+        self.connection.http2_prior_knowledge = enabled;
+        self.executor = rebuild_executor(self.custom_executor, self.connection)?;
+        Ok(self)
+    }
+
+    /// How long an idle connection is kept in the pool for reuse.
+    /// Defaults to 90 seconds.
+    /// Fails with [`ErrorKind::RequestBuildFailed`] if the client was
+    /// created with [`with_http_client`](Self::with_http_client).
+    pub fn with_pool_idle_timeout(mut self, timeout: Duration) -> Result<Self, Error> {
+        self.connection.pool_idle_timeout = Some(timeout);
+        self.executor = rebuild_executor(self.custom_executor, self.connection)?;
+        Ok(self)
+    }
+
+    /// Enable TCP keep-alive on connections, probing after `interval`
+    /// of inactivity. Disabled by default.
+    /// Fails with [`ErrorKind::RequestBuildFailed`] if the client was
+    /// created with [`with_http_client`](Self::with_http_client).
+    pub fn with_tcp_keepalive(mut self, interval: Duration) -> Result<Self, Error> {
+        self.connection.tcp_keepalive = Some(interval);
+        self.executor = rebuild_executor(self.custom_executor, self.connection)?;
+        Ok(self)
+    }
+}
+
+impl AuthClient {
+    /// Place a post only order, re-pricing it if it's rejected for
+    /// crossing the book. On rejection the current best bid and ask are
+    /// fetched and the order is resent one tick away from the opposite
+    /// side, up to `max_attempts` placements in total.
+    pub async fn place_post_only_with_reprice(
+        &self,
+        order: PlaceOrder<'_>,
+        max_attempts: NonZeroU32,
+        timeout: Option<Duration>,
+    ) -> Result<PlaceOrderResponse, Error> {
+        let mut order = PlaceOrder {
+            opts: Some(OrderOpts {
+                post_only: Some(true),
+                ..order.opts.unwrap_or_default()
+            }),
+            ..order
+        };
+
+        let mut attempts = 1;
+
+        loop {
+            let response = AuthExecutor::execute(self, &order, timeout).await?;
+
+            let err = match response.deserialize_partial() {
+                Ok(_) => return Ok(response),
+                Err(err) => err,
+            };
+
+            if attempts >= max_attempts.get()
+                || err.rejection_reason() != Some(RejectionReason::PostOnlyWouldTake)
+            {
+                return Err(err);
+            }
+
+            let market = Executor::execute(
+                self,
+                &GetMarket {
+                    market: order.market,
+                },
+                timeout,
+            )
+            .await?;
+
+            let market = market.deserialize()?;
+
+            let price = match order.side {
+                Side::Buy => market.ask.map(|ask| ask - market.price_increment),
+                Side::Sell => market.bid.map(|bid| bid + market.price_increment),
+            };
+
+            order.price = match price.and_then(PositiveDecimal::new) {
+                Some(price) => Some(price),
+                None => return Err(err),
+            };
+
+            attempts += 1;
+        }
+    }
+}
+
+impl AuthClient {
+    /// Fetch every fill between `start` and `end`, paging through
+    /// [`GetFills`](crate::endpoints::fills::GetFills) as needed.
+    pub async fn all_fills(
+        &self,
+        market: Option<&str>,
+        start: UnixTimestamp,
+        end: UnixTimestamp,
+    ) -> Result<FillHistory, Error> {
+        page_fills(self, market, start, end, None).await
+    }
+
+    /// Fetch the balances of every subaccount concurrently, keyed by
+    /// nickname. Failing to fetch one subaccount's balances, including
+    /// the exchange rejecting the request, doesn't affect the others.
+    /// Balances borrow from their response, so the checked responses are
+    /// returned for the caller to deserialize rather than the balances.
+    pub async fn all_subaccount_balances(
+        &self,
+    ) -> Result<HashMap<String, Result<GetSubaccountBalancesResponse, Error>>, Error> {
+        let subaccounts = AuthExecutor::execute(self, &GetSubaccounts, None).await?;
+        let subaccounts = subaccounts.deserialize_partial()?;
+
+        let balances = join_all(subaccounts.iter().map(|subaccount| async move {
+            let request = GetSubaccountBalances {
+                nickname: subaccount.nickname,
+            };
+
+            AuthExecutor::execute(self, &request, None)
+                .await
+                .and_then(Response::into_result)
+        }))
+        .await;
+
+        Ok(subaccounts
+            .iter()
+            .map(|subaccount| subaccount.nickname.to_owned())
+            .zip(balances)
+            .collect())
+    }
+
+    /// Transfer between accounts, first checking the source account has
+    /// enough of the coin available for withdrawal. Fails with
+    /// [`ErrorKind::InsufficientBalance`] without sending the transfer if
+    /// it doesn't. A `source` of `None` is the main account, whose
+    /// balances aren't visible to a client authenticated for a
+    /// subaccount, so that fails with [`ErrorKind::RequestBuildFailed`].
+    pub async fn transfer_checked(
+        &self,
+        transfer: &TransferBetweenSubaccounts<'_>,
+    ) -> Result<TransferBetweenSubaccountsResponse, Error> {
+        if transfer.source.is_none() && self.authenticator.has_subaccount() {
+            return Err(Error::new(ErrorKind::RequestBuildFailed).with_source(
+                "main account balances can't be checked by a client authenticated for a subaccount",
+            ));
+        }
+
+        let available = match transfer.source {
+            Some(nickname) => {
+                let balances =
+                    AuthExecutor::execute(self, &GetSubaccountBalances { nickname }, None).await?;
+
+                balances
+                    .deserialize()?
+                    .into_iter()
+                    .find(|b| b.coin == transfer.coin)
+                    .map(|b| b.available_for_withdrawal)
+            }
+            None => {
+                let balances = AuthExecutor::execute(self, &GetBalances, None).await?;
+
+                balances
+                    .deserialize()?
+                    .into_iter()
+                    .find(|b| b.coin == transfer.coin)
+                    .map(|b| b.available_for_withdrawal)
+            }
+        };
+
+        transfer
+            .check_available(available.unwrap_or_default())
+            .map_err(|e| Error::new(ErrorKind::InsufficientBalance).with_source(e))?;
+
+        AuthExecutor::execute(self, transfer, None).await
+    }
+
+    /// Cancel open orders, optionally in a single market, created more
+    /// than `age` ago. Orders are cancelled concurrently and the ids of
+    /// those successfully cancelled are returned. Orders the exchange
+    /// refuses to cancel, e.g. because they filled in the meantime, are
+    /// skipped. Any other failure, e.g. hitting rate limits, is returned
+    /// instead.
+    pub async fn cancel_orders_older_than(
+        &self,
+        market: Option<&str>,
+        age: Duration,
+    ) -> Result<Vec<u64>, Error> {
+        let orders = AuthExecutor::execute(self, &GetOpenOrders { market }, None).await?;
+        let orders = orders.deserialize()?;
+
+        let stale = orders_created_before(&orders, self.clock.now_utc() - age);
+
+        let cancelled = join_all(stale.into_iter().map(|order| async move {
+            let request = CancelOrder {
+                order_id: OrderId::Exchange(order.id),
+            };
+
+            let response = AuthExecutor::execute(self, &request, None).await;
+
+            match response.and_then(|r| r.deserialize_partial().map(|_| ())) {
+                Ok(()) => Ok(Some(order.id)),
+                Err(err) if err.kind() == ErrorKind::RejectedByExchange => Ok(None),
+                Err(err) => Err(err),
+            }
+        }))
+        .await;
+
+        cancelled
+            .into_iter()
+            .filter_map(Result::transpose)
+            .collect()
+    }
+
+    /// Fetch the stats of a future, checking the exchange accepted the
+    /// request. A future which doesn't exist fails with a
+    /// [`RejectionReason::UnknownFuture`] rejection, distinct from
+    /// transient failures which can be checked with
+    /// [`Error::is_retryable`].
+    pub async fn future_stats_checked(
+        &self,
+        future: &str,
+    ) -> Result<GetFutureStatsResponse, Error> {
+        Executor::execute(self, &GetFutureStats { future }, None)
+            .await?
+            .into_result()
+    }
+
+    /// Wait for an order to be filled or cancelled, polling its status.
+    /// The first wait is `poll_interval`, doubling after each poll up to
+    /// eight times that, so long waits don't use up rate limits. Fails
+    /// with [`ErrorKind::Timeout`] if the order is still open after
+    /// `timeout`.
+    pub async fn wait_for_order(
+        &self,
+        order_id: OrderId<'_>,
+        poll_interval: Duration,
+        timeout: Duration,
+    ) -> Result<GetOrderStatusResponse, Error> {
+        let deadline = Instant::now() + timeout;
+        let max_interval = poll_interval.saturating_mul(8);
+        let request = GetOrderStatus { order_id };
+
+        let mut interval = poll_interval;
+
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+
+            if remaining.is_zero() {
+                return Err(Error::new(ErrorKind::Timeout)
+                    .with_source(format!("order not closed within {:?}", timeout)));
+            }
+
+            let response = AuthExecutor::execute(self, &request, Some(remaining)).await?;
+
+            let status = response
+                .deserialize_partial()?
+                .status
+                .deserialize()
+                .map_err(|e| Error::new(ErrorKind::DeserializationFailed).with_source(e))?;
+
+            if status.is_terminal() {
+                return Ok(response);
+            }
+
+            tokio::time::sleep(interval.min(deadline.saturating_duration_since(Instant::now())))
+                .await;
+
+            interval = interval.saturating_mul(2).min(max_interval);
+        }
+    }
+
+    /// Execute a request against an endpoint not otherwise modelled by
+    /// the crate. The request is signed if `request.auth` is set.
+    pub async fn execute_raw(
+        &self,
+        request: &RawRequest<'_>,
+        timeout: Option<Duration>,
+    ) -> Result<RawResponse, Error> {
+        if request.auth {
+            AuthExecutor::execute(self, request, timeout).await
+        } else {
+            Executor::execute(self, request, timeout).await
+        }
+    }
+
+    /// Execute an authenticated request, abandoning it as soon as
+    /// `cancel` completes, e.g. on shutdown, rather than waiting for it
+    /// to finish or time out. Fails with [`ErrorKind::Cancelled`] if it's
+    /// abandoned, in which case the exchange may still have processed it.
+    pub async fn execute_with_cancel<R>(
+        &self,
+        request: &R,
+        timeout: Option<Duration>,
+        cancel: impl Future,
+    ) -> Result<R::Response, Error>
+    where
+        R: Request<true> + Send + Sync,
+    {
+        cancellable(AuthExecutor::execute(self, request, timeout), cancel).await
+    }
 }
 
 #[async_trait]
@@ -52,7 +771,17 @@ where
     R: Request<false> + Send + Sync,
 {
     async fn execute(&self, request: &R, timeout: Option<Duration>) -> Result<R::Response, Error> {
-        build_and_execute_request(request, timeout, &self.executor, None).await
+        build_and_execute_request(
+            request,
+            timeout,
+            &self.executor,
+            &self.base_url,
+            None,
+            self.observer.as_deref(),
+            self.retry_policy,
+            self.limits,
+        )
+        .await
     }
 }
 
@@ -62,26 +791,185 @@ where
     R: Request<true> + Send + Sync,
 {
     async fn execute(&self, request: &R, timeout: Option<Duration>) -> Result<R::Response, Error> {
-        build_and_execute_request(request, timeout, &self.executor, Some(&self.authenticator)).await
+        build_and_execute_request(
+            request,
+            timeout,
+            &self.executor,
+            &self.base_url,
+            Some((&self.authenticator, self.clock.as_ref())),
+            self.observer.as_deref(),
+            self.retry_policy,
+            self.limits,
+        )
+        .await
+    }
+}
+
+/// Run `fut` until it or `cancel` completes, dropping `fut` if `cancel`
+/// completes first.
+async fn cancellable<T>(
+    fut: impl Future<Output = Result<T, Error>>,
+    cancel: impl Future,
+) -> Result<T, Error> {
+    match select(Box::pin(fut), Box::pin(cancel)).await {
+        Either::Left((res, _)) => res,
+        Either::Right(_) => Err(Error::new(ErrorKind::Cancelled)),
+    }
+}
+
+/// Replace the crate's HTTP client with one using new connection options.
+/// A client given by the user can't be rebuilt without losing its own
+/// configuration, so that's refused.
+fn rebuild_executor(
+    custom_executor: bool,
+    options: ConnectionOptions,
+) -> Result<reqwest::Client, Error> {
+    if custom_executor {
+        return Err(Error::new(ErrorKind::RequestBuildFailed).with_source(
+            "connection options can't be set on a client created with `with_http_client`, \
+             configure the `reqwest::Client` instead",
+        ));
+    }
+
+    Ok(new_executor(options))
+}
+
+fn new_executor(options: ConnectionOptions) -> reqwest::Client {
+    let mut builder = reqwest::Client::builder().tcp_keepalive(options.tcp_keepalive);
+
+    if options.http2_prior_knowledge {
+        builder = builder.http2_prior_knowledge();
     }
+    if let Some(timeout) = options.pool_idle_timeout {
+        builder = builder.pool_idle_timeout(timeout);
+    }
+
+    // Decompression is handled by `reqwest` before the body is read
+    // with `bytes()`, so responses are unaffected.
+    #[cfg(feature = "compression")]
+    let builder = builder.gzip(true).brotli(true);
+
+    builder.build().expect("failed to initialise HTTP client")
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn build_and_execute_request<R, const AUTH: bool>(
     request: &R,
     timeout: Option<Duration>,
     executor: &reqwest::Client,
-    authenticator: Option<&Authenticator>,
+    base_url: &BaseUrl,
+    auth: Option<(&Authenticator, &dyn Clock)>,
+    observer: Option<&dyn RequestObserver>,
+    retry_policy: Option<RetryPolicy>,
+    limits: Limits,
 ) -> Result<R::Response, Error>
 where
     R: Request<AUTH>,
 {
+    let method = request.method();
+
     let path = request.path();
 
-    let path_with_params = build_path_with_params(&path, request.query_params().as_ref())?;
+    let deadline = timeout.map(|t| Instant::now() + t);
+
+    let mut retries = 0;
+
+    loop {
+        // Rebuilt for every attempt so authenticated requests are signed
+        // with a fresh timestamp.
+        let remaining = deadline.map(|d| d.saturating_duration_since(Instant::now()));
+
+        let req = build_request(request, &path, remaining, executor, base_url, auth, limits)?;
+
+        if let Some(observer) = observer {
+            observer.on_request(&method, &path);
+        }
+
+        let (status, result, latency) =
+            execute_request_timed(executor, req, limits.max_response_bytes).await;
+
+        if let Some(observer) = observer {
+            observer.on_response(&method, &path, status, latency);
+        }
+
+        let err = match result {
+            // "Please retry request" comes back as a regular response, so
+            // with a policy set the envelope is checked for it here rather
+            // than when the caller deserializes.
+            Ok(body) => match retry_policy.and_then(|_| retry_requested(&body)) {
+                Some(err) => err,
+                None => return Ok(body.into()),
+            },
+            Err(err) => err,
+        };
+
+        // A rate limited request wasn't processed, so it's safe to retry
+        // even if it isn't idempotent. Other failures are only retried if
+        // sending the request twice is harmless.
+        let policy = match retry_policy {
+            Some(policy) if retries < policy.max_retries && err.is_retryable_for(request) => policy,
+            _ => return Err(err),
+        };
+
+        let delay = err
+            .retry_after()
+            .unwrap_or_else(|| policy.backoff(retries, jitter()));
 
-    let url = format!("{}{}", BASE_URL, path_with_params.as_ref());
+        // Without a timeout there's no deadline, so a long `Retry-After`
+        // would otherwise stall the request for as long as it says.
+        if delay > policy.max_backoff || deadline.map_or(false, |d| Instant::now() + delay >= d) {
+            return Err(err);
+        }
+
+        tokio::time::sleep(delay).await;
 
-    let mut builder = executor.request(R::METHOD, url);
+        retries += 1;
+    }
+}
+
+/// The rejection in `body` if the exchange asked for the request to be
+/// retried.
+fn retry_requested(body: &[u8]) -> Option<Error> {
+    let err = FtxResponse::<IgnoredAny>::try_from(body)
+        .ok()?
+        .ensure_success()
+        .err()?;
+
+    (err.kind() == ErrorKind::RetryRequested).then_some(err)
+}
+
+/// A random number in `[0, 1)`. `RandomState` is randomly seeded, which
+/// is plenty for spreading out retries.
+fn jitter() -> f64 {
+    let bits = RandomState::new().build_hasher().finish();
+
+    (bits >> 11) as f64 / (1u64 << 53) as f64
+}
+
+fn build_request<R, const AUTH: bool>(
+    request: &R,
+    path: &str,
+    timeout: Option<Duration>,
+    executor: &reqwest::Client,
+    base_url: &BaseUrl,
+    auth: Option<(&Authenticator, &dyn Clock)>,
+    limits: Limits,
+) -> Result<reqwest::Request, Error>
+where
+    R: Request<AUTH>,
+{
+    let method = request.method();
+
+    let path_with_params = build_path_with_params(path, request, base_url, limits.max_url_len)?;
+
+    let url = format!(
+        "{}{}{}",
+        base_url.host,
+        base_url.api_prefix,
+        path_with_params.as_ref()
+    );
+
+    let mut builder = executor.request(method.clone(), url);
 
     if let Some(t) = timeout {
         builder = builder.timeout(t);
@@ -93,14 +981,13 @@ where
         None
     };
 
-    if let Some(authenticator) = authenticator {
-        let headers = authenticator.generate_auth_headers(
-            OffsetDateTime::now_utc()
-                .try_into()
-                .expect("timestamp will be > 0"),
-            &R::METHOD,
+    if let Some((authenticator, clock)) = auth {
+        let headers = authenticator.auth_headers_under_prefix_at(
+            clock.now_utc().try_into().expect("timestamp will be > 0"),
+            &method,
+            &base_url.api_prefix,
             &path_with_params,
-            payload.as_ref().map(String::as_str),
+            payload.as_deref(),
         )?;
 
         builder = builder.headers(headers);
@@ -112,73 +999,1175 @@ where
             .body(payload);
     }
 
-    let req = builder
+    builder
         .build()
-        .map_err(|e| Error::new(ErrorKind::RequestBuildFailed).with_source(e))?;
-
-    Ok(execute_request::<R::Response>(executor, req).await?)
+        .map_err(|e| Error::new(ErrorKind::RequestBuildFailed).with_source(e))
 }
 
-fn build_path_with_params<'a>(
+fn build_path_with_params<'a, R, const AUTH: bool>(
     path: &'a str,
-    query_params: Option<&QueryParams>,
-) -> Result<Cow<'a, str>, Error> {
-    if let Some(params) = query_params {
-        let query_string = serde_urlencoded::to_string(params).map_err(|e| {
-            let error = BuildUrlError::new(
-                format!(
-                    "error generating query string from params {:?}",
-                    query_params
-                ),
-                e,
-            );
-            Error::new(ErrorKind::InvalidUrl).with_source(error)
-        })?;
-
-        if !query_string.is_empty() {
-            let mut path: String = path.into();
-            path.push('?');
-            path.push_str(&query_string);
-            Ok(Cow::Owned(path))
-        } else {
-            Ok(Cow::Borrowed(path))
-        }
+    request: &R,
+    base_url: &BaseUrl,
+    max_url_len: usize,
+) -> Result<Cow<'a, str>, Error>
+where
+    R: Request<AUTH>,
+{
+    let mut path_with_params = String::with_capacity(path.len() + 64);
+
+    path_with_params.push_str(path);
+    path_with_params.push('?');
+
+    let start = path_with_params.len();
+
+    request.write_query(&mut QueryWriter::new(&mut path_with_params));
+
+    let path_with_params = if path_with_params.len() == start {
+        Cow::Borrowed(path)
     } else {
-        Ok(Cow::Borrowed(path))
+        Cow::Owned(path_with_params)
+    };
+
+    let url_len = base_url.len() + path_with_params.len();
+
+    if url_len > max_url_len {
+        return Err(Error::new(ErrorKind::InvalidUrl).with_source(format!(
+            "URL is {} bytes long, longer than the limit of {}",
+            url_len, max_url_len
+        )));
     }
+
+    Ok(path_with_params)
 }
 
-async fn execute_request<T>(client: &reqwest::Client, request: reqwest::Request) -> Result<T, Error>
-where
-    T: From<Bytes>,
-{
-    Ok(client
-        .execute(request)
-        .await
-        .map_err(|e| Error::from_status_code(e.status()).with_source(e))?
-        .bytes()
-        .await
-        .map_err(|e| Error::from_status_code(e.status()).with_source(e))?
-        .into())
+/// As [`execute_request`], also returning the time from sending the
+/// request until its body has been read.
+async fn execute_request_timed(
+    client: &reqwest::Client,
+    request: reqwest::Request,
+    max_response_bytes: Option<usize>,
+) -> (Option<StatusCode>, Result<Bytes, Error>, Duration) {
+    let start = Instant::now();
+
+    let (status, body) = execute_request(client, request, max_response_bytes).await;
+
+    (status, body, start.elapsed())
+}
+
+async fn execute_request(
+    client: &reqwest::Client,
+    request: reqwest::Request,
+    max_response_bytes: Option<usize>,
+) -> (Option<StatusCode>, Result<Bytes, Error>) {
+    let response = match client.execute(request).await {
+        Ok(response) => response,
+        Err(e) => {
+            return (
+                e.status(),
+                Err(Error::from_status_code(e.status()).with_source(e)),
+            )
+        }
+    };
+
+    let status = response.status();
+
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        let retry_after = parse_retry_after(response.headers(), OffsetDateTime::now_utc());
+        let err = Error::from_status_code(Some(status)).with_retry_after(retry_after);
+
+        return (
+            Some(status),
+            Err(with_body_message(err, response, max_response_bytes).await),
+        );
+    }
+
+    if status.is_server_error() {
+        let err = Error::from_status_code(Some(status));
+
+        return (
+            Some(status),
+            Err(with_body_message(err, response, max_response_bytes).await),
+        );
+    }
+
+    (Some(status), read_body(response, max_response_bytes).await)
 }
 
-#[derive(Debug)]
-struct BuildUrlError(String, BoxError);
+/// Keep whatever the exchange said about a failed request, the body is
+/// otherwise discarded.
+async fn with_body_message(
+    err: Error,
+    response: reqwest::Response,
+    max_response_bytes: Option<usize>,
+) -> Error {
+    let body = read_body(response, max_response_bytes).await.ok();
+    let message = body
+        .as_deref()
+        .and_then(|body| FtxResponse::<IgnoredAny>::try_from(body).ok()?.error());
 
-impl BuildUrlError {
-    fn new(reason: String, source: impl Into<BoxError>) -> Self {
-        Self(reason, source.into())
+    match message {
+        Some(message) => err.with_exchange_message(message),
+        None => err,
     }
 }
 
-impl fmt::Display for BuildUrlError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.0)
+async fn read_body(
+    response: reqwest::Response,
+    max_response_bytes: Option<usize>,
+) -> Result<Bytes, Error> {
+    match max_response_bytes {
+        Some(limit) => read_body_with_limit(response, limit).await,
+        None => response
+            .bytes()
+            .await
+            .map_err(|e| Error::from_status_code(e.status()).with_source(e)),
+    }
+}
+
+/// Parse a `Retry-After` header, given either in seconds or as an HTTP
+/// date, which is taken relative to `now`. A date in the past means no
+/// wait.
+fn parse_retry_after(headers: &HeaderMap, now: OffsetDateTime) -> Option<Duration> {
+    let val = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+
+    if let Ok(secs) = val.parse() {
+        return Some(Duration::from_secs(secs));
+    }
+
+    let date = OffsetDateTime::parse(val, &Rfc2822).ok()?;
+
+    Some((date - now).try_into().unwrap_or_default())
+}
+
+async fn read_body_with_limit(
+    mut response: reqwest::Response,
+    limit: usize,
+) -> Result<Bytes, Error> {
+    let too_large = || {
+        Error::new(ErrorKind::ResponseTooLarge)
+            .with_source(format!("response body exceeds {} bytes", limit))
+    };
+
+    if response
+        .content_length()
+        .map_or(false, |len| len > limit as u64)
+    {
+        return Err(too_large());
+    }
+
+    let mut body = BytesMut::new();
+
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| Error::from_status_code(e.status()).with_source(e))?
+    {
+        if body.len() + chunk.len() > limit {
+            return Err(too_large());
+        }
+
+        body.extend_from_slice(&chunk);
     }
+
+    Ok(body.freeze())
 }
 
-impl StdError for BuildUrlError {
-    fn source(&self) -> Option<&(dyn StdError + 'static)> {
-        Some(self.1.as_ref())
+#[cfg(test)]
+mod tests {
+    use std::{
+        error::Error as StdError,
+        io::{Read, Write},
+        net::TcpListener,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Mutex,
+        },
+        thread,
+    };
+
+    use rust_decimal::Decimal;
+    use time::macros::datetime;
+
+    use crate::{
+        auth::{PrivateKey, PublicKey, Subaccount},
+        endpoints::orders::OrderStatus,
+    };
+
+    use super::*;
+
+    type ObservedResponse = (Method, String, Option<StatusCode>, Duration);
+
+    /// Records every call to its hooks.
+    #[derive(Default)]
+    struct RecordingObserver {
+        requests: Mutex<Vec<(Method, String)>>,
+        responses: Mutex<Vec<ObservedResponse>>,
+    }
+
+    impl RequestObserver for Arc<RecordingObserver> {
+        fn on_request(&self, method: &Method, path: &str) {
+            self.requests
+                .lock()
+                .unwrap()
+                .push((method.clone(), path.to_owned()));
+        }
+
+        fn on_response(
+            &self,
+            method: &Method,
+            path: &str,
+            status: Option<StatusCode>,
+            latency: Duration,
+        ) {
+            self.responses
+                .lock()
+                .unwrap()
+                .push((method.clone(), path.to_owned(), status, latency));
+        }
+    }
+
+    #[tokio::test]
+    async fn observer_sees_request_and_response() {
+        let observer = Arc::new(RecordingObserver::default());
+
+        let (url, _) = serve_sequence(vec![OK]);
+        let client = Client::new()
+            .with_base_url(url)
+            .unwrap()
+            .with_observer(observer.clone());
+
+        let request = RawRequest {
+            query: Some(vec![("market", "BTC-PERP".into())]),
+            ..get_markets()
+        };
+
+        client.execute_raw(&request, None).await.unwrap();
+
+        // The path excludes the base URL's prefix and the query string.
+        assert_eq!(
+            *observer.requests.lock().unwrap(),
+            vec![(Method::GET, "/markets".to_owned())]
+        );
+
+        let responses = observer.responses.lock().unwrap();
+        assert_eq!(responses.len(), 1);
+
+        let (method, path, status, latency) = &responses[0];
+        assert_eq!(method, Method::GET);
+        assert_eq!(path, "/markets");
+        assert_eq!(*status, Some(StatusCode::OK));
+        assert!(*latency > Duration::ZERO);
+    }
+
+    /// Serve a single HTTP request on a local port, replying with
+    /// `response` verbatim.
+    fn serve_once(response: Vec<u8>) -> String {
+        serve_once_capturing(response).0
+    }
+
+    /// As [`serve_once`], also returning the request head received.
+    fn serve_once_capturing(response: Vec<u8>) -> (String, thread::JoinHandle<Vec<u8>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+
+            let mut request = Vec::new();
+            let mut buf = [0; 1024];
+
+            while !request.ends_with(b"\r\n\r\n") {
+                let n = stream.read(&mut buf).unwrap();
+                request.extend_from_slice(&buf[..n]);
+            }
+
+            let _ = stream.write_all(&response);
+            request
+        });
+
+        (format!("http://{}/", addr), server)
+    }
+
+    /// Serve `responses` in order, one per connection, counting the
+    /// requests received. An empty response closes the connection
+    /// without replying.
+    fn serve_sequence(responses: Vec<&'static [u8]>) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let served = Arc::new(AtomicUsize::new(0));
+        let counter = served.clone();
+
+        thread::spawn(move || {
+            for response in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+
+                let mut request = Vec::new();
+                let mut buf = [0; 1024];
+
+                // Requests with a body may arrive in the same read as the
+                // head, so look for the end of the head anywhere.
+                while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                    let n = stream.read(&mut buf).unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                }
+
+                counter.fetch_add(1, Ordering::SeqCst);
+                let _ = stream.write_all(response);
+            }
+        });
+
+        (format!("http://{}/api", addr), served)
+    }
+
+    const RETRY_REQUESTED: &[u8] = b"HTTP/1.1 200 OK\r\nConnection: close\r\nContent-Length: 48\r\n\r\n{\"success\":false,\"error\":\"Please retry request\"}";
+
+    const UNAVAILABLE: &[u8] =
+        b"HTTP/1.1 503 Service Unavailable\r\nConnection: close\r\nContent-Length: 0\r\n\r\n";
+
+    const OPEN_ORDERS: &[u8] = concat!(
+        "HTTP/1.1 200 OK\r\nConnection: close\r\nContent-Length: 350\r\n\r\n",
+        r#"{"success":true,"result":[{"createdAt":"2019-03-05T09:56:55.728933+00:00","#,
+        r#""filledSize":10,"future":"XRP-PERP","id":9596912,"market":"XRP-PERP","#,
+        r#""price":0.306525,"avgFillPrice":0.306526,"remainingSize":31421,"side":"sell","#,
+        r#""size":31431,"status":"open","type":"limit","reduceOnly":false,"ioc":false,"#,
+        r#""postOnly":false,"clientId":null,"liquidation":false}]}"#,
+    )
+    .as_bytes();
+
+    const ORDER_ALREADY_CLOSED: &[u8] = b"HTTP/1.1 200 OK\r\nConnection: close\r\nContent-Length: 48\r\n\r\n{\"success\":false,\"error\":\"Order already closed\"}";
+
+    const SUBACCOUNTS: &[u8] = concat!(
+        "HTTP/1.1 200 OK\r\nConnection: close\r\nContent-Length: 116\r\n\r\n",
+        r#"{"success":true,"result":[{"nickname":"sub1","deletable":true,"#,
+        r#""editable":true,"special":false,"competition":false}]}"#,
+    )
+    .as_bytes();
+
+    const NO_SUCH_SUBACCOUNT: &[u8] = b"HTTP/1.1 200 OK\r\nConnection: close\r\nContent-Length: 46\r\n\r\n{\"success\":false,\"error\":\"No such subaccount\"}";
+
+    /// Closes the connection without responding.
+    const DROPPED: &[u8] = b"";
+
+    const RATE_LIMITED: &[u8] =
+        b"HTTP/1.1 429 Too Many Requests\r\nConnection: close\r\nContent-Length: 0\r\n\r\n";
+
+    const RATE_LIMITED_RETRY_AFTER: &[u8] = b"HTTP/1.1 429 Too Many Requests\r\nConnection: close\r\nRetry-After: 0\r\nContent-Length: 0\r\n\r\n";
+
+    const RATE_LIMITED_FOR_A_DAY: &[u8] = b"HTTP/1.1 429 Too Many Requests\r\nConnection: close\r\nRetry-After: 86400\r\nContent-Length: 0\r\n\r\n";
+
+    const RATE_LIMITED_MESSAGE: &[u8] = b"HTTP/1.1 429 Too Many Requests\r\nConnection: close\r\nContent-Length: 49\r\n\r\n{\"success\":false,\"error\":\"Do not send more than\"}";
+
+    const ORDER_OPEN: &[u8] = concat!(
+        "HTTP/1.1 200 OK\r\nConnection: close\r\nContent-Length: 348\r\n\r\n",
+        r#"{"success":true,"result":{"createdAt":"2019-03-05T09:56:55.728933+00:00","#,
+        r#""filledSize":10,"future":"XRP-PERP","id":9596912,"market":"XRP-PERP","#,
+        r#""price":0.306525,"avgFillPrice":0.306526,"remainingSize":31421,"side":"sell","#,
+        r#""size":31431,"status":"open","type":"limit","reduceOnly":false,"ioc":false,"#,
+        r#""postOnly":false,"clientId":null,"liquidation":false}}"#,
+    )
+    .as_bytes();
+
+    const ORDER_CLOSED: &[u8] = concat!(
+        "HTTP/1.1 200 OK\r\nConnection: close\r\nContent-Length: 350\r\n\r\n",
+        r#"{"success":true,"result":{"createdAt":"2019-03-05T09:56:55.728933+00:00","#,
+        r#""filledSize":10,"future":"XRP-PERP","id":9596912,"market":"XRP-PERP","#,
+        r#""price":0.306525,"avgFillPrice":0.306526,"remainingSize":31421,"side":"sell","#,
+        r#""size":31431,"status":"closed","type":"limit","reduceOnly":false,"ioc":false,"#,
+        r#""postOnly":false,"clientId":null,"liquidation":false}}"#,
+    )
+    .as_bytes();
+
+    const OK: &[u8] = b"HTTP/1.1 200 OK\r\nConnection: close\r\nContent-Length: 28\r\n\r\n{\"success\":true,\"result\":[]}";
+
+    fn get_markets() -> RawRequest<'static> {
+        RawRequest {
+            method: Method::GET,
+            path: "/markets".into(),
+            query: None,
+            body: None,
+            auth: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn retry_rate_limited() {
+        let policy = RetryPolicy {
+            max_retries: 2,
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(10),
+        };
+
+        let (url, served) = serve_sequence(vec![RATE_LIMITED, RATE_LIMITED_RETRY_AFTER, OK]);
+        let client = Client::new()
+            .with_base_url(url)
+            .unwrap()
+            .with_retry_policy(policy);
+
+        let response = client.execute_raw(&get_markets(), None).await.unwrap();
+        assert_eq!(response.deserialize().unwrap().get(), "[]");
+        assert_eq!(served.load(Ordering::SeqCst), 3);
+
+        // Without a policy the first rejection is returned.
+        let (url, served) = serve_sequence(vec![RATE_LIMITED_RETRY_AFTER, OK]);
+        let client = Client::new().with_base_url(url).unwrap();
+
+        let err = client.execute_raw(&get_markets(), None).await.unwrap_err();
+        assert_eq!(err.to_string(), "rate limits exceeded");
+        assert_eq!(err.retry_after(), Some(Duration::ZERO));
+        assert_eq!(served.load(Ordering::SeqCst), 1);
+
+        // The exchange's explanation is kept.
+        let (url, _) = serve_sequence(vec![RATE_LIMITED_MESSAGE]);
+        let client = Client::new().with_base_url(url).unwrap();
+
+        let err = client.execute_raw(&get_markets(), None).await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::RateLimitExceeded);
+        assert_eq!(err.exchange_message(), Some("Do not send more than"));
+
+        // Retries stop when they'd overrun the request's timeout.
+        let (url, served) = serve_sequence(vec![RATE_LIMITED, OK]);
+        let client = Client::new()
+            .with_base_url(url)
+            .unwrap()
+            .with_retry_policy(RetryPolicy {
+                initial_backoff: Duration::from_secs(60),
+                max_backoff: Duration::from_secs(60),
+                ..policy
+            });
+
+        let err = client
+            .execute_raw(&get_markets(), Some(Duration::from_secs(5)))
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "rate limits exceeded");
+        assert_eq!(served.load(Ordering::SeqCst), 1);
+
+        // Nor do they wait longer than the policy allows, even without a
+        // timeout.
+        let (url, served) = serve_sequence(vec![RATE_LIMITED_FOR_A_DAY, OK]);
+        let client = Client::new()
+            .with_base_url(url)
+            .unwrap()
+            .with_retry_policy(policy);
+
+        let err = client.execute_raw(&get_markets(), None).await.unwrap_err();
+        assert_eq!(err.retry_after(), Some(Duration::from_secs(86400)));
+        assert_eq!(served.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn retry_requested_by_exchange() {
+        let policy = RetryPolicy {
+            max_retries: 2,
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(10),
+        };
+
+        let (url, served) = serve_sequence(vec![RETRY_REQUESTED, OK]);
+        let client = Client::new()
+            .with_base_url(url)
+            .unwrap()
+            .with_retry_policy(policy);
+
+        let response = client.execute_raw(&get_markets(), None).await.unwrap();
+        assert_eq!(response.deserialize().unwrap().get(), "[]");
+        assert_eq!(served.load(Ordering::SeqCst), 2);
+
+        // Without a policy the rejection is left for the caller.
+        let (url, served) = serve_sequence(vec![RETRY_REQUESTED, OK]);
+        let client = Client::new().with_base_url(url).unwrap();
+
+        let response = client.execute_raw(&get_markets(), None).await.unwrap();
+        assert_eq!(
+            response.deserialize().unwrap_err().kind(),
+            ErrorKind::RetryRequested
+        );
+        assert_eq!(served.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn retry_server_error() {
+        let policy = RetryPolicy {
+            max_retries: 2,
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(10),
+        };
+
+        let (url, served) = serve_sequence(vec![UNAVAILABLE, OK]);
+        let client = Client::new()
+            .with_base_url(url)
+            .unwrap()
+            .with_retry_policy(policy);
+
+        let response = client.execute_raw(&get_markets(), None).await.unwrap();
+        assert_eq!(response.deserialize().unwrap().get(), "[]");
+        assert_eq!(served.load(Ordering::SeqCst), 2);
+
+        let authenticator = Authenticator::new(
+            PublicKey::new("public"),
+            PrivateKey::new("YAGN-Np3au9igIMqIAPiJTF1zy9heo55_FNfYEru"),
+            None,
+        )
+        .unwrap();
+
+        let order = PlaceOrder {
+            market: "BTC-PERP",
+            side: Side::Buy,
+            price: None,
+            size: PositiveDecimal::new(Decimal::ONE).unwrap(),
+            client_id: None,
+            opts: None,
+        };
+
+        let (url, served) = serve_sequence(vec![UNAVAILABLE, OK]);
+        let client = AuthClient::new(authenticator)
+            .with_base_url(url)
+            .unwrap()
+            .with_retry_policy(policy);
+
+        let err = match AuthExecutor::execute(&client, &order, None).await {
+            Ok(_) => panic!("order was resent"),
+            Err(err) => err,
+        };
+        assert_eq!(
+            err.kind(),
+            ErrorKind::RequestExecutionFailed(Some(StatusCode::SERVICE_UNAVAILABLE))
+        );
+        assert_eq!(served.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn retry_dropped_connection() {
+        let policy = RetryPolicy {
+            max_retries: 2,
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(10),
+        };
+
+        let (url, served) = serve_sequence(vec![DROPPED, OK]);
+        let client = Client::new()
+            .with_base_url(url)
+            .unwrap()
+            .with_retry_policy(policy);
+
+        let response = client.execute_raw(&get_markets(), None).await.unwrap();
+        assert_eq!(response.deserialize().unwrap().get(), "[]");
+        assert_eq!(served.load(Ordering::SeqCst), 2);
+
+        let authenticator = Authenticator::new(
+            PublicKey::new("public"),
+            PrivateKey::new("YAGN-Np3au9igIMqIAPiJTF1zy9heo55_FNfYEru"),
+            None,
+        )
+        .unwrap();
+
+        let order = PlaceOrder {
+            market: "BTC-PERP",
+            side: Side::Buy,
+            price: None,
+            size: PositiveDecimal::new(Decimal::ONE).unwrap(),
+            client_id: None,
+            opts: None,
+        };
+
+        // The exchange may have placed the order before the connection
+        // dropped, so it isn't sent again.
+        let (url, served) = serve_sequence(vec![DROPPED, OK]);
+        let client = AuthClient::new(authenticator.clone())
+            .with_base_url(url)
+            .unwrap()
+            .with_retry_policy(policy);
+
+        let err = match AuthExecutor::execute(&client, &order, None).await {
+            Ok(_) => panic!("order was resent"),
+            Err(err) => err,
+        };
+        assert_eq!(err.kind(), ErrorKind::RequestExecutionFailed(None));
+        assert_eq!(served.load(Ordering::SeqCst), 1);
+
+        // With a client id the exchange rejects a duplicate.
+        let (url, served) = serve_sequence(vec![DROPPED, OK]);
+        let client = AuthClient::new(authenticator)
+            .with_base_url(url)
+            .unwrap()
+            .with_retry_policy(policy);
+
+        let order = PlaceOrder {
+            client_id: Some("order-1"),
+            ..order
+        };
+
+        AuthExecutor::execute(&client, &order, None).await.unwrap();
+        assert_eq!(served.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn wait_for_order() {
+        let authenticator = Authenticator::new(
+            PublicKey::new("public"),
+            PrivateKey::new("YAGN-Np3au9igIMqIAPiJTF1zy9heo55_FNfYEru"),
+            None,
+        )
+        .unwrap();
+
+        let (url, served) = serve_sequence(vec![ORDER_OPEN, ORDER_OPEN, ORDER_CLOSED]);
+        let client = AuthClient::new(authenticator.clone())
+            .with_base_url(url)
+            .unwrap();
+
+        let response = client
+            .wait_for_order(
+                OrderId::Exchange(9596912),
+                Duration::from_millis(1),
+                Duration::from_secs(5),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.deserialize().unwrap().status, OrderStatus::Closed);
+        assert_eq!(served.load(Ordering::SeqCst), 3);
+
+        // Waits of 50, 100 then 200ms, so only three polls fit in 300ms
+        // rather than six without backing off.
+        let (url, served) = serve_sequence(vec![ORDER_OPEN; 6]);
+        let client = AuthClient::new(authenticator).with_base_url(url).unwrap();
+
+        let err = client
+            .wait_for_order(
+                OrderId::Exchange(9596912),
+                Duration::from_millis(50),
+                Duration::from_millis(300),
+            )
+            .await
+            .unwrap_err();
+
+        assert_eq!(err.to_string(), "timed out");
+        assert_eq!(served.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn execute_with_cancel() {
+        // Accepts connections but never responds.
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        thread::spawn(move || {
+            let _streams: Vec<_> = listener.incoming().collect();
+        });
+
+        let client = Client::new()
+            .with_base_url(format!("http://{}/api", addr))
+            .unwrap();
+
+        let start = Instant::now();
+
+        let err = client
+            .execute_with_cancel(
+                &get_markets(),
+                Some(Duration::from_secs(30)),
+                tokio::time::sleep(Duration::from_millis(50)),
+            )
+            .await
+            .unwrap_err();
+
+        assert_eq!(err.to_string(), "request cancelled");
+        assert!(start.elapsed() < Duration::from_secs(5));
+
+        let (url, _) = serve_sequence(vec![OK]);
+        let client = Client::new().with_base_url(url).unwrap();
+
+        let response = client
+            .execute_with_cancel(&get_markets(), None, futures_util::future::pending::<()>())
+            .await
+            .unwrap();
+        assert_eq!(response.deserialize().unwrap().get(), "[]");
+    }
+
+    #[test]
+    fn retry_after() {
+        let now = datetime!(2015-10-21 07:28:00 UTC);
+
+        let parse = |val: &'static str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(RETRY_AFTER, HeaderValue::from_static(val));
+            parse_retry_after(&headers, now)
+        };
+
+        assert_eq!(parse("120"), Some(Duration::from_secs(120)));
+        assert_eq!(parse(" 0 "), Some(Duration::ZERO));
+        assert_eq!(
+            parse("Wed, 21 Oct 2015 07:28:30 GMT"),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(parse("Wed, 21 Oct 2015 07:27:00 GMT"), Some(Duration::ZERO));
+        assert_eq!(parse("-1"), None);
+        assert_eq!(parse("soon"), None);
+        assert_eq!(parse_retry_after(&HeaderMap::new(), now), None);
+    }
+
+    #[test]
+    fn retry_backoff() {
+        let policy = RetryPolicy {
+            max_retries: 10,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(1),
+        };
+
+        assert_eq!(policy.backoff(0, 0.0), Duration::from_millis(100));
+        assert_eq!(policy.backoff(1, 0.0), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3, 0.0), Duration::from_millis(800));
+        assert_eq!(policy.backoff(4, 0.0), Duration::from_secs(1));
+        assert_eq!(policy.backoff(u32::MAX, 0.0), Duration::from_secs(1));
+        assert_eq!(policy.backoff(1, 0.5), Duration::from_millis(150));
+
+        for _ in 0..100 {
+            let jitter = jitter();
+            assert!((0.0..1.0).contains(&jitter));
+        }
+    }
+
+    async fn get_with_limit(response: Vec<u8>, limit: usize) -> Result<Bytes, Error> {
+        let executor = reqwest::Client::new();
+        let request = executor.get(serve_once(response)).build().unwrap();
+
+        execute_request(&executor, request, Some(limit)).await.1
+    }
+
+    #[tokio::test]
+    async fn latency_is_recorded() {
+        let executor = reqwest::Client::new();
+        let request = executor
+            .get(serve_once(
+                b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\n{}".to_vec(),
+            ))
+            .build()
+            .unwrap();
+
+        let (status, body, latency) = execute_request_timed(&executor, request, None).await;
+
+        assert_eq!(status, Some(StatusCode::OK));
+        assert_eq!(body.unwrap(), "{}");
+        assert!(latency > Duration::ZERO);
+    }
+
+    #[tokio::test]
+    async fn max_response_bytes() {
+        let body = vec![b'x'; 1024];
+
+        let mut response = b"HTTP/1.1 200 OK\r\nContent-Length: 1024\r\n\r\n".to_vec();
+        response.extend_from_slice(&body);
+
+        assert_eq!(get_with_limit(response.clone(), 1024).await.unwrap(), body);
+        assert_eq!(
+            get_with_limit(response, 1023)
+                .await
+                .unwrap_err()
+                .to_string(),
+            "response body too large"
+        );
+
+        // Without a content length the limit is enforced while streaming.
+        let mut response = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n".to_vec();
+        for _ in 0..4 {
+            response.extend_from_slice(b"400\r\n");
+            response.extend_from_slice(&body);
+            response.extend_from_slice(b"\r\n");
+        }
+        response.extend_from_slice(b"0\r\n\r\n");
+
+        assert_eq!(
+            get_with_limit(response, 2048)
+                .await
+                .unwrap_err()
+                .to_string(),
+            "response body too large"
+        );
+    }
+
+    #[test]
+    fn fixed_clock_signature() {
+        let authenticator = Authenticator::new(
+            PublicKey::new("public"),
+            PrivateKey::new("YAGN-Np3au9igIMqIAPiJTF1zy9heo55_FNfYEru"),
+            None,
+        )
+        .unwrap();
+        let clock = FixedClock(datetime!(2021-04-05 21:52:38.822 UTC));
+
+        let request = RawRequest {
+            method: Method::GET,
+            path: "/spot_margin/borrow_rates".into(),
+            query: None,
+            body: None,
+            auth: true,
+        };
+
+        let req = build_request::<_, true>(
+            &request,
+            &request.path,
+            None,
+            &reqwest::Client::new(),
+            &BaseUrl::default(),
+            Some((&authenticator, &clock)),
+            Limits::default(),
+        )
+        .unwrap();
+
+        assert_eq!(req.headers()["FTX-TS"], "1617659558822");
+        assert_eq!(
+            req.headers()["FTX-SIGN"],
+            "421c580094ab840e832071c75602f1f7d1504901175589284e6ce81ff163ec0b"
+        );
+    }
+
+    #[test]
+    fn base_url() {
+        let authenticator = Authenticator::new(
+            PublicKey::new("public"),
+            PrivateKey::new("YAGN-Np3au9igIMqIAPiJTF1zy9heo55_FNfYEru"),
+            None,
+        )
+        .unwrap();
+        let clock = FixedClock(datetime!(2021-04-05 21:52:38.822 UTC));
+
+        let request = RawRequest {
+            method: Method::GET,
+            path: "/spot_margin/borrow_rates".into(),
+            query: None,
+            body: None,
+            auth: true,
+        };
+
+        let build = |base_url: &BaseUrl| {
+            build_request::<_, true>(
+                &request,
+                &request.path,
+                None,
+                &reqwest::Client::new(),
+                base_url,
+                Some((&authenticator, &clock)),
+                Limits::default(),
+            )
+            .unwrap()
+        };
+
+        // Only the API prefix is signed, so moving host keeps the
+        // signature.
+        let base_url = BaseUrl::parse("http://127.0.0.1:8080/api/").unwrap();
+        let req = build(&base_url);
+
+        assert_eq!(
+            req.url().as_str(),
+            "http://127.0.0.1:8080/api/spot_margin/borrow_rates"
+        );
+        assert_eq!(
+            req.headers()["FTX-SIGN"],
+            "421c580094ab840e832071c75602f1f7d1504901175589284e6ce81ff163ec0b"
+        );
+
+        let base_url = BaseUrl::parse("https://example.com/ftx/api").unwrap();
+        let req = build(&base_url);
+
+        assert_eq!(
+            req.url().as_str(),
+            "https://example.com/ftx/api/spot_margin/borrow_rates"
+        );
+        assert_eq!(
+            req.headers(),
+            &authenticator
+                .auth_headers_under_prefix_at(
+                    UnixTimestamp::new(1617659558822),
+                    &Method::GET,
+                    "/ftx/api",
+                    "/spot_margin/borrow_rates",
+                    None,
+                )
+                .unwrap()
+        );
+        assert_ne!(
+            req.headers()["FTX-SIGN"],
+            "421c580094ab840e832071c75602f1f7d1504901175589284e6ce81ff163ec0b"
+        );
+
+        for url in [
+            "not a url",
+            "ftp://127.0.0.1/api",
+            "http://127.0.0.1/api?x=1",
+        ] {
+            assert_eq!(BaseUrl::parse(url).unwrap_err().to_string(), "invalid URL");
+        }
+    }
+
+    #[tokio::test]
+    async fn with_base_url() {
+        let url = serve_once(
+            b"HTTP/1.1 200 OK\r\nContent-Length: 28\r\n\r\n{\"success\":true,\"result\":[]}"
+                .to_vec(),
+        );
+
+        let client = Client::new().with_base_url(url + "api").unwrap();
+
+        let request = RawRequest {
+            method: Method::GET,
+            path: "/markets".into(),
+            query: None,
+            body: None,
+            auth: false,
+        };
+
+        let response = client.execute_raw(&request, None).await.unwrap();
+        assert_eq!(response.deserialize().unwrap().get(), "[]");
+    }
+
+    #[tokio::test]
+    async fn with_http_client() {
+        let (url, server) = serve_once_capturing(
+            b"HTTP/1.1 200 OK\r\nContent-Length: 28\r\n\r\n{\"success\":true,\"result\":[]}"
+                .to_vec(),
+        );
+
+        let executor = reqwest::Client::builder()
+            .user_agent("my-trading-stack")
+            .build()
+            .unwrap();
+
+        let client = Client::with_http_client(executor)
+            .with_base_url(url + "api")
+            .unwrap();
+
+        let request = RawRequest {
+            method: Method::GET,
+            path: "/markets".into(),
+            query: None,
+            body: None,
+            auth: false,
+        };
+
+        client.execute_raw(&request, None).await.unwrap();
+
+        let head = String::from_utf8(server.join().unwrap()).unwrap();
+        assert!(head.starts_with("GET /api/markets HTTP/1.1\r\n"));
+        assert!(head.contains("user-agent: my-trading-stack\r\n"));
+    }
+
+    #[test]
+    fn connection_options_on_injected_client() {
+        let err = Client::with_http_client(reqwest::Client::new())
+            .with_tcp_keepalive(Duration::from_secs(15))
+            .err()
+            .unwrap();
+
+        assert_eq!(err.kind(), ErrorKind::RequestBuildFailed);
+        assert!(err
+            .source()
+            .unwrap()
+            .to_string()
+            .contains("created with `with_http_client`"));
+    }
+
+    #[test]
+    fn connection_options_on_injected_auth_client() {
+        let authenticator = Authenticator::new(
+            PublicKey::new("public"),
+            PrivateKey::new("YAGN-Np3au9igIMqIAPiJTF1zy9heo55_FNfYEru"),
+            None,
+        )
+        .unwrap();
+
+        let err = AuthClient::with_http_client(authenticator, reqwest::Client::new())
+            .with_pool_idle_timeout(Duration::from_secs(30))
+            .err()
+            .unwrap();
+
+        assert_eq!(err.kind(), ErrorKind::RequestBuildFailed);
+    }
+
+    #[tokio::test]
+    async fn transfer_checked_from_subaccount_client() {
+        let authenticator = Authenticator::new(
+            PublicKey::new("public"),
+            PrivateKey::new("YAGN-Np3au9igIMqIAPiJTF1zy9heo55_FNfYEru"),
+            Some(Subaccount::new("sub")),
+        )
+        .unwrap();
+
+        // Nothing is sent, so any request would fail to connect.
+        let client = AuthClient::new(authenticator)
+            .with_base_url("http://127.0.0.1:1/api")
+            .unwrap();
+
+        let err = client
+            .transfer_checked(&TransferBetweenSubaccounts {
+                coin: "USD",
+                size: Decimal::ONE,
+                source: None,
+                destination: Some("sub"),
+            })
+            .await
+            .err()
+            .unwrap();
+
+        assert_eq!(err.kind(), ErrorKind::RequestBuildFailed);
+    }
+
+    #[tokio::test]
+    async fn all_subaccount_balances_rejected() {
+        let authenticator = Authenticator::new(
+            PublicKey::new("public"),
+            PrivateKey::new("YAGN-Np3au9igIMqIAPiJTF1zy9heo55_FNfYEru"),
+            None,
+        )
+        .unwrap();
+
+        let (url, _) = serve_sequence(vec![SUBACCOUNTS, NO_SUCH_SUBACCOUNT]);
+        let client = AuthClient::new(authenticator).with_base_url(url).unwrap();
+
+        let balances = client.all_subaccount_balances().await.unwrap();
+
+        let err = balances["sub1"].as_ref().err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::RejectedByExchange);
+        assert_eq!(err.exchange_message(), Some("No such subaccount"));
+    }
+
+    #[tokio::test]
+    async fn cancel_orders_older_than() {
+        let authenticator = Authenticator::new(
+            PublicKey::new("public"),
+            PrivateKey::new("YAGN-Np3au9igIMqIAPiJTF1zy9heo55_FNfYEru"),
+            None,
+        )
+        .unwrap();
+
+        // An order which closed before it could be cancelled is skipped.
+        let (url, _) = serve_sequence(vec![OPEN_ORDERS, ORDER_ALREADY_CLOSED]);
+        let client = AuthClient::new(authenticator.clone())
+            .with_base_url(url)
+            .unwrap();
+
+        let cancelled = client
+            .cancel_orders_older_than(None, Duration::from_secs(60))
+            .await
+            .unwrap();
+        assert!(cancelled.is_empty());
+
+        // Other failures aren't hidden.
+        let (url, _) = serve_sequence(vec![OPEN_ORDERS, UNAVAILABLE]);
+        let client = AuthClient::new(authenticator).with_base_url(url).unwrap();
+
+        let err = client
+            .cancel_orders_older_than(None, Duration::from_secs(60))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.kind(),
+            ErrorKind::RequestExecutionFailed(Some(StatusCode::SERVICE_UNAVAILABLE))
+        );
+    }
+
+    #[test]
+    fn overlong_url() {
+        let request = RawRequest {
+            method: Method::GET,
+            path: "/markets".into(),
+            query: Some(vec![("market", "BTC-PERP".repeat(1024))]),
+            body: None,
+            auth: false,
+        };
+
+        let err = build_path_with_params::<_, false>(
+            &request.path,
+            &request,
+            &BaseUrl::default(),
+            DEFAULT_MAX_URL_LEN,
+        )
+        .unwrap_err();
+
+        assert_eq!(err.to_string(), "invalid URL");
+        assert!(err.source().unwrap().to_string().contains("8192"));
+
+        assert!(build_path_with_params::<_, false>(
+            &request.path,
+            &request,
+            &BaseUrl::default(),
+            16 * 1024
+        )
+        .is_ok());
+    }
+
+    /// Accept a single connection and return the first bytes the client
+    /// sends, before dropping it.
+    async fn first_bytes_sent(executor: &reqwest::Client) -> Vec<u8> {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+
+            let mut buf = [0; 16];
+            stream.read_exact(&mut buf).unwrap();
+            buf.to_vec()
+        });
+
+        let _ = executor.get(format!("http://{}/", addr)).send().await;
+
+        server.join().unwrap()
+    }
+
+    #[tokio::test]
+    async fn connection_options() {
+        let client = Client::new()
+            .with_http2_prior_knowledge(true)
+            .and_then(|c| c.with_pool_idle_timeout(Duration::from_secs(30)))
+            .and_then(|c| c.with_tcp_keepalive(Duration::from_secs(15)))
+            .unwrap();
+
+        assert_eq!(
+            client.connection,
+            ConnectionOptions {
+                http2_prior_knowledge: true,
+                pool_idle_timeout: Some(Duration::from_secs(30)),
+                tcp_keepalive: Some(Duration::from_secs(15)),
+            }
+        );
+        assert_eq!(
+            first_bytes_sent(&client.executor).await,
+            b"PRI * HTTP/2.0\r\n"
+        );
+
+        let client = client.with_http2_prior_knowledge(false).unwrap();
+        assert_eq!(
+            first_bytes_sent(&client.executor).await,
+            b"GET / HTTP/1.1\r\n"
+        );
+    }
+
+    #[cfg(feature = "compression")]
+    #[tokio::test]
+    async fn compression_enabled() {
+        // `{"success":true,"result":[]}` gzipped.
+        const GZIPPED: &[u8] = b"\x1f\x8b\x08\x00\x00\x00\x00\x00\x02\x03\xab\x56\x2a\x2e\x4d\x4e\x4e\x2d\x2e\x56\xb2\x2a\x29\x2a\x4d\xd5\x51\x2a\x4a\x2d\x2e\xcd\x29\x51\xb2\x8a\x8e\xad\x05\x00\xb2\x17\x5c\xe2\x1c\x00\x00\x00";
+
+        let mut response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Encoding: gzip\r\nContent-Length: {}\r\n\r\n",
+            GZIPPED.len()
+        )
+        .into_bytes();
+        response.extend_from_slice(GZIPPED);
+
+        let (url, server) = serve_once_capturing(response);
+        let client = Client::new().with_base_url(url + "api").unwrap();
+
+        let response = client.execute_raw(&get_markets(), None).await.unwrap();
+        assert_eq!(response.deserialize().unwrap().get(), "[]");
+
+        let head = String::from_utf8(server.join().unwrap()).unwrap();
+        let accept_encoding = head
+            .lines()
+            .find_map(|line| line.strip_prefix("accept-encoding: "))
+            .unwrap();
+
+        assert!(accept_encoding.contains("gzip"));
+        assert!(accept_encoding.contains("br"));
     }
 }
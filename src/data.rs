@@ -87,6 +87,30 @@ impl WindowLength {
             Days(multiple) => 86400 * (multiple.0 as u64),
         }
     }
+
+    /// Parse a resolution in seconds, returning `None` if it isn't one
+    /// supported by FTX for market or index candles. Every
+    /// `WindowLength` is a supported resolution.
+    pub fn from_secs(secs: u64) -> Option<Self> {
+        use WindowLength::*;
+
+        let window = match secs {
+            15 => FifteenSeconds,
+            60 => OneMinute,
+            300 => FiveMinutes,
+            900 => FifteenMinutes,
+            3600 => OneHour,
+            14400 => FourHours,
+            _ if secs % 86400 == 0 => {
+                let days = u8::try_from(secs / 86400).ok().and_then(NonZeroU8::new)?;
+
+                Days(WindowLengthDays::new(days)?)
+            }
+            _ => return None,
+        };
+
+        Some(window)
+    }
 }
 
 /// A multiple of the max window length of one day. Must be less than
@@ -208,6 +232,31 @@ impl FtxDateTime {
     pub fn get(&self) -> OffsetDateTime {
         self.0
     }
+
+    #[cfg(feature = "chrono")]
+    pub fn to_chrono(&self) -> chrono::DateTime<chrono::Utc> {
+        use chrono::TimeZone;
+
+        chrono::Utc
+            .timestamp_opt(self.0.unix_timestamp(), self.0.nanosecond())
+            .single()
+            .expect("`time` dates are always within the range supported by `chrono`")
+    }
+
+    #[cfg(feature = "chrono")]
+    pub fn from_chrono(
+        dt: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Self, time::error::ComponentRange> {
+        OffsetDateTime::from_unix_timestamp(dt.timestamp())?
+            .replace_nanosecond(dt.timestamp_subsec_nanos())
+            .map(Self)
+    }
+}
+
+impl From<OffsetDateTime> for FtxDateTime {
+    fn from(dt: OffsetDateTime) -> Self {
+        Self(dt)
+    }
 }
 
 impl<'de> Deserialize<'de> for FtxDateTime {
@@ -300,6 +349,39 @@ impl TryFrom<Decimal> for PositiveDecimal {
     }
 }
 
+/// An order size which has been checked to be a multiple of a market's
+/// size increment. Use [`Market::validate_size`] to create one.
+///
+/// [`Market::validate_size`]: crate::endpoints::markets::Market::validate_size
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub struct OrderSize(PositiveDecimal);
+
+impl OrderSize {
+    pub fn new(
+        size: PositiveDecimal,
+        size_increment: Decimal,
+    ) -> Result<Self, TryFromDecimalError> {
+        if size_increment > Decimal::ZERO && (size.get() % size_increment).is_zero() {
+            Ok(Self(size))
+        } else {
+            Err(TryFromDecimalError {
+                d: size.get(),
+                err: format!("value must be a multiple of {}", size_increment).into(),
+            })
+        }
+    }
+
+    pub fn get(&self) -> PositiveDecimal {
+        self.0
+    }
+}
+
+impl From<OrderSize> for PositiveDecimal {
+    fn from(size: OrderSize) -> Self {
+        size.0
+    }
+}
+
 #[derive(Debug)]
 pub struct TryFromDecimalError {
     d: Decimal,
@@ -333,4 +415,30 @@ mod tests {
         // Confirm datetime matches
         assert_eq!(datetime!(2019-03-05 09:56:55.728933 +00:00), de[0].0);
     }
+
+    #[test]
+    fn window_length_from_secs() {
+        for secs in [15, 60, 300, 900, 3600, 14400, 86400, 30 * 86400] {
+            let window = WindowLength::from_secs(secs).unwrap();
+
+            assert_eq!(window.to_secs(), secs);
+        }
+
+        for secs in [0, 1, 30, 120, 7200, 86401, 31 * 86400, 256 * 86400] {
+            assert_eq!(WindowLength::from_secs(secs), None);
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn datetime_chrono_round_trip() {
+        use chrono::{TimeZone, Utc};
+
+        let dt = FtxDateTime(datetime!(2019-03-05 09:56:55.728933 +00:00));
+
+        let expected = Utc.timestamp_opt(1551779815, 728_933_000).single().unwrap();
+
+        assert_eq!(dt.to_chrono(), expected);
+        assert_eq!(FtxDateTime::from_chrono(expected).unwrap(), dt);
+    }
 }
@@ -1,17 +1,20 @@
 use bytes::Bytes;
 use core::fmt;
-use reqwest::Method;
+use http::Method;
 use rust_decimal::{prelude::ToPrimitive, Decimal};
 use serde::{de, Deserialize, Serialize};
 use std::{
+    collections::HashMap,
     convert::{TryFrom, TryInto},
     error::Error as StdError,
     num::NonZeroU32,
 };
 
-use crate::{data::Side, private::Sealed, Json, OptJson, QueryParams, Request};
+use crate::{
+    data::Side, error::Error, private::Sealed, Json, OptJson, QueryParams, Request, Response,
+};
 
-use super::macros::response;
+use super::{futures::underlying_from_name, macros::response};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum AccountLeverage {
@@ -97,6 +100,53 @@ impl fmt::Display for InvalidAccountLeverageError {
 
 impl StdError for InvalidAccountLeverageError {}
 
+/// Type of an account. Values not known to this crate are kept as
+/// [`AccountType::Other`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AccountType<'a> {
+    Individual,
+    Institutional,
+    Other(&'a str),
+}
+
+impl<'a> AccountType<'a> {
+    pub fn as_str(&self) -> &'a str {
+        match self {
+            Self::Individual => "individual",
+            Self::Institutional => "institutional",
+            Self::Other(s) => s,
+        }
+    }
+}
+
+impl<'a> From<&'a str> for AccountType<'a> {
+    fn from(s: &'a str) -> Self {
+        match s {
+            "individual" => Self::Individual,
+            "institutional" => Self::Institutional,
+            _ => Self::Other(s),
+        }
+    }
+}
+
+impl<'a> Serialize for AccountType<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de: 'a, 'a> Deserialize<'de> for AccountType<'a> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        <&'a str>::deserialize(deserializer).map(Into::into)
+    }
+}
+
 /// Retrieve account and position information.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct GetAccountInformation;
@@ -119,9 +169,18 @@ response!(
     AccountInformationPartial<'a>
 );
 
+impl GetAccountInformationResponse {
+    /// Deserialize the response straight into an owned [`AccountSnapshot`].
+    pub fn snapshot(&self) -> Result<AccountSnapshot, Error> {
+        self.deserialize().map(|info| info.snapshot())
+    }
+}
+
 /// Retrieve current positions.
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct GetPositions {
+    /// Also fill in the `recent_*` and `cumulative_*` fields of each
+    /// position, which are otherwise `None`.
     pub show_avg_price: Option<bool>,
 }
 
@@ -148,7 +207,7 @@ response!(
     Vec<PositionPartial<'a>>
 );
 
-/// Change an account's leverage.
+/// Change an account's leverage, i.e. [`AccountInformation::leverage`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Hash)]
 pub struct ChangeAccountLeverage {
     pub leverage: AccountLeverage,
@@ -177,13 +236,18 @@ response!(ChangeAccountLeverageResponse, (), ());
 #[cfg_attr(feature = "deny-unknown-fields", serde(deny_unknown_fields))]
 pub struct AccountInformation<'a> {
     pub account_identifier: u64,
-    pub account_type: Option<&'a str>,
+    #[serde(borrow)]
+    pub account_type: Option<AccountType<'a>>,
     pub backstop_provider: bool,
     pub collateral: Decimal,
     pub free_collateral: Decimal,
     pub initial_margin_requirement: Decimal,
     pub maintenance_margin_requirement: Decimal,
+    /// The account's maximum leverage, changed with
+    /// [`ChangeAccountLeverage`].
     pub leverage: AccountLeverage,
+    /// The maximum leverage applied to futures positions, which the
+    /// exchange may cap below `leverage`.
     pub futures_leverage: AccountLeverage,
     pub liquidating: bool,
     pub margin_fraction: Option<Decimal>,
@@ -204,6 +268,47 @@ pub struct AccountInformation<'a> {
     pub positions: Vec<Position<'a>>,
 }
 
+impl<'a> AccountInformation<'a> {
+    pub fn leverage_limits(&self) -> LeverageLimits {
+        LeverageLimits {
+            leverage: self.leverage,
+            futures_leverage: self.futures_leverage,
+        }
+    }
+
+    /// Copy all fields into an owned [`AccountSnapshot`].
+    pub fn snapshot(&self) -> AccountSnapshot {
+        AccountSnapshot {
+            account_identifier: self.account_identifier,
+            account_type: self.account_type.map(|t| t.as_str().to_owned()),
+            backstop_provider: self.backstop_provider,
+            collateral: self.collateral,
+            free_collateral: self.free_collateral,
+            initial_margin_requirement: self.initial_margin_requirement,
+            maintenance_margin_requirement: self.maintenance_margin_requirement,
+            leverage: self.leverage,
+            futures_leverage: self.futures_leverage,
+            liquidating: self.liquidating,
+            margin_fraction: self.margin_fraction,
+            open_margin_fraction: self.open_margin_fraction,
+            maker_fee: self.maker_fee,
+            taker_fee: self.taker_fee,
+            total_account_value: self.total_account_value,
+            total_account_nav: self.total_account_nav,
+            total_position_size: self.total_position_size,
+            charge_interest_on_negative_usd: self.charge_interest_on_negative_usd,
+            position_limit: self.position_limit,
+            position_limit_used: self.position_limit_used,
+            use_ftt_collateral: self.use_ftt_collateral,
+            username: self.username.to_owned(),
+            spot_lending_enabled: self.spot_lending_enabled,
+            spot_margin_enabled: self.spot_margin_enabled,
+            spot_margin_withdrawals_enabled: self.spot_margin_withdrawals_enabled,
+            positions: self.positions.iter().map(Position::snapshot).collect(),
+        }
+    }
+}
+
 impl<'a> TryFrom<AccountInformationPartial<'a>> for AccountInformation<'a> {
     type Error = serde_json::Error;
 
@@ -249,7 +354,8 @@ impl<'a> TryFrom<AccountInformationPartial<'a>> for AccountInformation<'a> {
 pub struct AccountInformationPartial<'a> {
     #[serde(borrow)]
     pub account_identifier: Json<'a, u64>,
-    pub account_type: Option<&'a str>,
+    #[serde(borrow)]
+    pub account_type: Option<AccountType<'a>>,
     #[serde(borrow)]
     pub backstop_provider: Json<'a, bool>,
     #[serde(borrow)]
@@ -299,6 +405,89 @@ pub struct AccountInformationPartial<'a> {
     pub positions: Vec<PositionPartial<'a>>,
 }
 
+impl<'a> AccountInformationPartial<'a> {
+    /// Deserialize just the spot lending and margin flags.
+    pub fn spot_margin_status(&self) -> Result<SpotMarginStatus, serde_json::Error> {
+        Ok(SpotMarginStatus {
+            spot_lending_enabled: self.spot_lending_enabled.deserialize()?,
+            spot_margin_enabled: self.spot_margin_enabled.deserialize()?,
+            spot_margin_withdrawals_enabled: self.spot_margin_withdrawals_enabled.deserialize()?,
+        })
+    }
+
+    /// Deserialize just the account and futures leverage.
+    pub fn leverage_limits(&self) -> Result<LeverageLimits, serde_json::Error> {
+        Ok(LeverageLimits {
+            leverage: self.leverage.deserialize()?,
+            futures_leverage: self.futures_leverage.deserialize()?,
+        })
+    }
+
+    /// Deserialize just the `liquidating` flag.
+    pub fn is_liquidating(&self) -> Result<bool, serde_json::Error> {
+        self.liquidating.deserialize()
+    }
+
+    /// Deserialize just the fields needed to alert on liquidation.
+    pub fn liquidation_status(&self) -> Result<LiquidationStatus, serde_json::Error> {
+        Ok(LiquidationStatus {
+            liquidating: self.liquidating.deserialize()?,
+            margin_fraction: self.margin_fraction.deserialize()?,
+            maintenance_margin_requirement: self.maintenance_margin_requirement.deserialize()?,
+        })
+    }
+}
+
+/// Liquidation state from [`AccountInformation`]. The `Display` output
+/// is a one line summary suitable for alerts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiquidationStatus {
+    pub liquidating: bool,
+    /// `None` if the account has no positions.
+    pub margin_fraction: Option<Decimal>,
+    pub maintenance_margin_requirement: Decimal,
+}
+
+impl fmt::Display for LiquidationStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(if self.liquidating {
+            "account is being liquidated"
+        } else {
+            "account is not being liquidated"
+        })?;
+
+        if let Some(margin_fraction) = self.margin_fraction {
+            write!(
+                f,
+                ", margin fraction {} (maintenance {})",
+                margin_fraction, self.maintenance_margin_requirement
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Leverage limits from [`AccountInformation`]. Only `leverage` is set
+/// by [`ChangeAccountLeverage`], `futures_leverage` is what's applied to
+/// futures positions and may be capped lower by the exchange.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LeverageLimits {
+    pub leverage: AccountLeverage,
+    pub futures_leverage: AccountLeverage,
+}
+
+/// Spot lending and margin flags from [`AccountInformation`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpotMarginStatus {
+    pub spot_lending_enabled: bool,
+    pub spot_margin_enabled: bool,
+    pub spot_margin_withdrawals_enabled: bool,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "deny-unknown-fields", serde(deny_unknown_fields))]
@@ -325,6 +514,34 @@ pub struct Position<'a> {
     pub cumulative_sell_size: Option<Decimal>,
 }
 
+impl<'a> Position<'a> {
+    /// Copy all fields into an owned [`PositionSnapshot`].
+    pub fn snapshot(&self) -> PositionSnapshot {
+        PositionSnapshot {
+            cost: self.cost,
+            entry_price: self.entry_price,
+            estimated_liquidation_price: self.estimated_liquidation_price,
+            future: self.future.to_owned(),
+            initial_margin_requirement: self.initial_margin_requirement,
+            maintenance_margin_requirement: self.maintenance_margin_requirement,
+            long_order_size: self.long_order_size,
+            short_order_size: self.short_order_size,
+            net_size: self.net_size,
+            open_size: self.open_size,
+            realized_pnl: self.realized_pnl,
+            side: self.side,
+            size: self.size,
+            unrealized_pnl: self.unrealized_pnl,
+            collateral_used: self.collateral_used,
+            recent_average_open_price: self.recent_average_open_price,
+            recent_break_even_price: self.recent_break_even_price,
+            recent_pnl: self.recent_pnl,
+            cumulative_buy_size: self.cumulative_buy_size,
+            cumulative_sell_size: self.cumulative_sell_size,
+        }
+    }
+}
+
 impl<'a> TryFrom<PositionPartial<'a>> for Position<'a> {
     type Error = serde_json::Error;
 
@@ -354,6 +571,111 @@ impl<'a> TryFrom<PositionPartial<'a>> for Position<'a> {
     }
 }
 
+/// Sum realized and unrealized PnL across `positions`, returned as
+/// `(realized, unrealized)`. Only the two PnL fields are deserialized.
+pub fn total_pnl(
+    positions: &[PositionPartial<'_>],
+) -> Result<(Decimal, Decimal), serde_json::Error> {
+    positions.iter().try_fold(
+        (Decimal::ZERO, Decimal::ZERO),
+        |(realized, unrealized), p| {
+            Ok((
+                realized + p.realized_pnl.deserialize()?,
+                unrealized + p.unrealized_pnl.deserialize()?,
+            ))
+        },
+    )
+}
+
+/// Sum the signed notional `net_size * entry_price` of `positions`,
+/// grouped by the underlying parsed from each future's name. Flat
+/// positions are skipped.
+pub fn net_exposure_by_underlying(
+    positions: &[Position<'_>],
+) -> Result<HashMap<String, Decimal>, ExposureError> {
+    let mut exposures: HashMap<String, Decimal> = HashMap::new();
+
+    for position in positions.iter().filter(|p| !p.net_size.is_zero()) {
+        let err = || ExposureError {
+            future: position.future.to_owned(),
+        };
+
+        let underlying = underlying_from_name(position.future).ok_or_else(err)?;
+
+        let notional = position
+            .entry_price
+            .and_then(|price| position.net_size.checked_mul(price))
+            .ok_or_else(err)?;
+
+        let exposure = exposures.entry(underlying.to_owned()).or_default();
+        *exposure = exposure.checked_add(notional).ok_or_else(err)?;
+    }
+
+    Ok(exposures)
+}
+
+/// Whether two sets of positions agree on the net size held in each
+/// future, e.g. those from [`AccountInformation`] and [`GetPositions`],
+/// to detect a stale snapshot. Flat positions, which the exchange keeps
+/// reporting for futures traded before, are ignored.
+pub fn positions_match(account_positions: &[Position<'_>], standalone: &[Position<'_>]) -> bool {
+    fn net_sizes<'a>(positions: &[Position<'a>]) -> HashMap<&'a str, Decimal> {
+        positions
+            .iter()
+            .filter(|p| !p.net_size.is_zero())
+            .map(|p| (p.future, p.net_size))
+            .collect()
+    }
+
+    net_sizes(account_positions) == net_sizes(standalone)
+}
+
+/// Estimate the mark price at which a position would be liquidated, for
+/// what-if analysis. `size` is the absolute position size and
+/// `collateral` the collateral backing it.
+///
+/// This treats the position as if it were isolated: liquidation happens
+/// once `collateral` plus the position's unrealized PnL falls to `mmr`
+/// times its notional value. Under cross margin other positions, fees
+/// and the exchange's mark price smoothing all move the real price, so
+/// prefer `Position::estimated_liquidation_price` for open positions.
+///
+/// Returns zero for a long which can't be liquidated and `None` if
+/// there is no such price, e.g. for a zero size.
+pub fn estimate_liquidation_price(
+    side: Side,
+    entry: Decimal,
+    size: Decimal,
+    collateral: Decimal,
+    mmr: Decimal,
+) -> Option<Decimal> {
+    let notional = size.checked_mul(entry)?;
+
+    let price = match side {
+        Side::Buy => notional
+            .checked_sub(collateral)?
+            .checked_div(size.checked_mul(Decimal::ONE - mmr)?)?,
+        Side::Sell => notional
+            .checked_add(collateral)?
+            .checked_div(size.checked_mul(Decimal::ONE + mmr)?)?,
+    };
+
+    Some(price.max(Decimal::ZERO))
+}
+
+#[derive(Debug)]
+pub struct ExposureError {
+    future: String,
+}
+
+impl fmt::Display for ExposureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to compute exposure of {} position", self.future)
+    }
+}
+
+impl StdError for ExposureError {}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "deny-unknown-fields", serde(deny_unknown_fields))]
@@ -399,15 +721,70 @@ pub struct PositionPartial<'a> {
     pub cumulative_sell_size: OptJson<'a, Decimal>,
 }
 
+/// A fully owned copy of [`AccountInformation`] which can be persisted and
+/// outlive the response it was read from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountSnapshot {
+    pub account_identifier: u64,
+    pub account_type: Option<String>,
+    pub backstop_provider: bool,
+    pub collateral: Decimal,
+    pub free_collateral: Decimal,
+    pub initial_margin_requirement: Decimal,
+    pub maintenance_margin_requirement: Decimal,
+    pub leverage: AccountLeverage,
+    pub futures_leverage: AccountLeverage,
+    pub liquidating: bool,
+    pub margin_fraction: Option<Decimal>,
+    pub open_margin_fraction: Option<Decimal>,
+    pub maker_fee: Decimal,
+    pub taker_fee: Decimal,
+    pub total_account_value: Decimal,
+    pub total_account_nav: Decimal,
+    pub total_position_size: Decimal,
+    pub charge_interest_on_negative_usd: bool,
+    pub position_limit: Option<Decimal>,
+    pub position_limit_used: Option<Decimal>,
+    pub use_ftt_collateral: bool,
+    pub username: String,
+    pub spot_lending_enabled: bool,
+    pub spot_margin_enabled: bool,
+    pub spot_margin_withdrawals_enabled: bool,
+    pub positions: Vec<PositionSnapshot>,
+}
+
+/// A fully owned copy of a [`Position`], see [`AccountSnapshot`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PositionSnapshot {
+    pub cost: Decimal,
+    pub entry_price: Option<Decimal>,
+    pub estimated_liquidation_price: Option<Decimal>,
+    pub future: String,
+    pub initial_margin_requirement: Decimal,
+    pub maintenance_margin_requirement: Decimal,
+    pub long_order_size: Decimal,
+    pub short_order_size: Decimal,
+    pub net_size: Decimal,
+    pub open_size: Decimal,
+    pub realized_pnl: Decimal,
+    pub side: Side,
+    pub size: Decimal,
+    pub unrealized_pnl: Decimal,
+    pub collateral_used: Decimal,
+    pub recent_average_open_price: Option<Decimal>,
+    pub recent_break_even_price: Option<Decimal>,
+    pub recent_pnl: Option<Decimal>,
+    pub cumulative_buy_size: Option<Decimal>,
+    pub cumulative_sell_size: Option<Decimal>,
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::Response;
-
     use super::*;
 
-    #[test]
-    fn get_account_information() {
-        let json = r#"
+    const GET_ACCOUNT_INFORMATION_JSON: &str = r#"
 {
   "result": {
     "accountIdentifier": 1338857,
@@ -457,17 +834,163 @@ mod tests {
   }
 }
 "#;
+
+    #[test]
+    fn get_account_information() {
+        let json = GET_ACCOUNT_INFORMATION_JSON;
         let response = GetAccountInformationResponse(json.as_bytes().into());
 
         let from_partial: AccountInformation<'_> =
             response.deserialize_partial().unwrap().try_into().unwrap();
 
         assert_eq!(response.deserialize().unwrap(), from_partial);
+
+        assert_eq!(
+            response
+                .deserialize_partial()
+                .unwrap()
+                .spot_margin_status()
+                .unwrap(),
+            SpotMarginStatus {
+                spot_lending_enabled: true,
+                spot_margin_enabled: true,
+                spot_margin_withdrawals_enabled: true,
+            }
+        );
+
+        let snapshot = response.snapshot().unwrap();
+
+        assert_eq!(snapshot, from_partial.snapshot());
+        assert_eq!(snapshot.positions[0].future, "VET-PERP");
+
+        let serialized = serde_json::to_string(&snapshot).unwrap();
+
+        assert_eq!(
+            serde_json::from_str::<AccountSnapshot>(&serialized).unwrap(),
+            snapshot
+        );
     }
 
     #[test]
-    fn get_positions() {
-        let json = r#"
+    fn account_type() {
+        let response =
+            GetAccountInformationResponse(GET_ACCOUNT_INFORMATION_JSON.as_bytes().into());
+
+        assert_eq!(response.deserialize().unwrap().account_type, None);
+
+        let json = GET_ACCOUNT_INFORMATION_JSON.replace(
+            r#""accountType": null"#,
+            r#""accountType": "institutional""#,
+        );
+        let response = GetAccountInformationResponse(json.into());
+
+        assert_eq!(
+            response.deserialize().unwrap().account_type,
+            Some(AccountType::Institutional)
+        );
+        assert_eq!(
+            response.snapshot().unwrap().account_type.as_deref(),
+            Some("institutional")
+        );
+
+        assert_eq!(
+            serde_json::from_str::<AccountType<'_>>(r#""vip""#).unwrap(),
+            AccountType::Other("vip")
+        );
+    }
+
+    #[test]
+    fn leverage_limits() {
+        let response =
+            GetAccountInformationResponse(GET_ACCOUNT_INFORMATION_JSON.as_bytes().into());
+
+        let json = GET_ACCOUNT_INFORMATION_JSON
+            .replace(r#""futuresLeverage": 3.0"#, r#""futuresLeverage": 2.0"#);
+        let capped = GetAccountInformationResponse(json.into());
+
+        assert_eq!(
+            response
+                .deserialize_partial()
+                .unwrap()
+                .leverage_limits()
+                .unwrap(),
+            LeverageLimits {
+                leverage: AccountLeverage::Three,
+                futures_leverage: AccountLeverage::Three,
+            }
+        );
+
+        let limits = capped
+            .deserialize_partial()
+            .unwrap()
+            .leverage_limits()
+            .unwrap();
+
+        assert_eq!(limits.leverage, AccountLeverage::Three);
+        assert_eq!(limits.futures_leverage, AccountLeverage::Two);
+        assert_eq!(capped.deserialize().unwrap().leverage_limits(), limits);
+    }
+
+    #[test]
+    fn liquidation_status() {
+        let response =
+            GetAccountInformationResponse(GET_ACCOUNT_INFORMATION_JSON.as_bytes().into());
+        let info = response.deserialize_partial().unwrap();
+
+        assert!(!info.is_liquidating().unwrap());
+        assert_eq!(
+            info.liquidation_status().unwrap().to_string(),
+            "account is not being liquidated"
+        );
+
+        let json = GET_ACCOUNT_INFORMATION_JSON
+            .replace(r#""liquidating": false"#, r#""liquidating": true"#)
+            .replace(r#""marginFraction": null"#, r#""marginFraction": 0.025"#);
+        let response = GetAccountInformationResponse(json.into());
+        let info = response.deserialize_partial().unwrap();
+
+        assert!(info.is_liquidating().unwrap());
+        assert_eq!(
+            info.liquidation_status().unwrap().to_string(),
+            "account is being liquidated, margin fraction 0.025 (maintenance 0.03)"
+        );
+    }
+
+    #[test]
+    fn liquidation_price() {
+        let (entry, size, mmr) = (
+            Decimal::new(100, 0),
+            Decimal::new(10, 0),
+            Decimal::new(3, 2),
+        );
+
+        // 200 + 10 * (p - 100) = 0.03 * 10 * p
+        assert_eq!(
+            estimate_liquidation_price(Side::Buy, entry, size, Decimal::new(200, 0), mmr)
+                .unwrap()
+                .round_dp(4),
+            Decimal::new(824742, 4)
+        );
+
+        // 200 + 10 * (100 - p) = 0.03 * 10 * p
+        assert_eq!(
+            estimate_liquidation_price(Side::Sell, entry, size, Decimal::new(200, 0), mmr)
+                .unwrap()
+                .round_dp(4),
+            Decimal::new(1165049, 4)
+        );
+
+        assert_eq!(
+            estimate_liquidation_price(Side::Buy, entry, size, Decimal::new(2000, 0), mmr),
+            Some(Decimal::ZERO)
+        );
+        assert_eq!(
+            estimate_liquidation_price(Side::Buy, entry, Decimal::ZERO, Decimal::ZERO, mmr),
+            None
+        );
+    }
+
+    const GET_POSITIONS_JSON: &str = r#"
 {
   "result": [
     {
@@ -491,10 +1014,36 @@ mod tests {
       "side": "buy",
       "size": 0,
       "unrealizedPnl": 0
+    },
+    {
+      "collateralUsed": 0.3,
+      "cost": -31.7906,
+      "cumulativeBuySize": 0,
+      "cumulativeSellSize": 1,
+      "entryPrice": 138.22,
+      "estimatedLiquidationPrice": 152.1,
+      "future": "ETH-PERP",
+      "initialMarginRequirement": 0.1,
+      "longOrderSize": 1744.55,
+      "maintenanceMarginRequirement": 0.04,
+      "netSize": -0.23,
+      "openSize": 1744.32,
+      "realizedPnl": 3.39441714,
+      "recentAverageOpenPrice": 135.31,
+      "recentBreakEvenPrice": 135.31,
+      "recentPnl": 1.2,
+      "shortOrderSize": 1732.09,
+      "side": "sell",
+      "size": 0.23,
+      "unrealizedPnl": -0.1512
     }
   ]
 }
 "#;
+
+    #[test]
+    fn get_positions() {
+        let json = GET_POSITIONS_JSON;
         let response = GetPositionsResponse(json.as_bytes().into());
 
         let from_partial: Vec<Position<'_>> = response
@@ -505,5 +1054,57 @@ mod tests {
             .collect();
 
         assert_eq!(response.deserialize().unwrap(), from_partial);
+
+        assert_eq!(
+            total_pnl(&response.deserialize_partial().unwrap()).unwrap(),
+            (Decimal::new(-187232956, 8), Decimal::new(-1512, 4))
+        );
+    }
+
+    #[test]
+    fn net_exposure() {
+        let response = GetPositionsResponse(GET_POSITIONS_JSON.as_bytes().into());
+        let mut positions = response.deserialize().unwrap();
+
+        // Offset the short perp position with a long dated future.
+        positions.push(Position {
+            future: "ETH-0325",
+            net_size: Decimal::new(23, 2),
+            side: Side::Buy,
+            ..positions[1]
+        });
+
+        let exposures = net_exposure_by_underlying(&positions).unwrap();
+        assert_eq!(exposures.len(), 1);
+        assert_eq!(exposures["ETH"], Decimal::ZERO);
+
+        positions.pop();
+        let exposures = net_exposure_by_underlying(&positions).unwrap();
+        assert_eq!(exposures["ETH"], Decimal::new(-317906, 4));
+
+        positions[1].entry_price = None;
+        assert!(net_exposure_by_underlying(&positions).is_err());
+    }
+
+    #[test]
+    fn matching_positions() {
+        let response = GetPositionsResponse(GET_POSITIONS_JSON.as_bytes().into());
+        let positions = response.deserialize().unwrap();
+
+        let mut reordered = positions.clone();
+        reordered.reverse();
+        assert!(positions_match(&positions, &reordered));
+
+        // Only a flat position is left, which doesn't count.
+        assert!(positions_match(&positions[1..], &positions));
+
+        let response =
+            GetAccountInformationResponse(GET_ACCOUNT_INFORMATION_JSON.as_bytes().into());
+        let account = response.deserialize().unwrap();
+        assert!(!positions_match(&account.positions, &positions));
+
+        let mut resized = positions.clone();
+        resized[1].net_size = Decimal::new(-1, 1);
+        assert!(!positions_match(&resized, &positions));
     }
 }
@@ -1,17 +1,25 @@
-use std::convert::TryFrom;
+use std::{
+    collections::HashSet,
+    convert::{TryFrom, TryInto},
+    time::Duration,
+};
 
 use bytes::Bytes;
-use reqwest::Method;
+use http::Method;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    data::{FtxDateTime, Side, SortOrder, UnixTimestamp},
+    data::{
+        BaseCurrency, FtxDateTime, InvalidUnixTimestamp, QuoteCurrency, Side, SortOrder,
+        UnixTimestamp,
+    },
+    error::{Error, ErrorKind},
     private::Sealed,
-    Json, QueryParams, Request,
+    AuthExecutor, Json, QueryParams, QueryWriter, Request, Response,
 };
 
-use super::macros::response;
+use super::{macros::response, orders::Order};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum FillType {
@@ -77,12 +85,164 @@ impl<'a> Request<true> for GetFills<'a> {
 
         Some(params)
     }
+
+    fn write_query(&self, query: &mut QueryWriter<'_>) {
+        if let Some(market) = self.market {
+            query.append("market", market);
+        }
+        if let Some(start_time) = self.start_time {
+            query.append_uint("start_time", start_time.get());
+        }
+        if let Some(end_time) = self.end_time {
+            query.append_uint("end_time", end_time.get());
+        }
+        if let Some(order_id) = self.order_id {
+            query.append_uint("orderId", order_id);
+        }
+        if matches!(self.order, Some(SortOrder::Ascending)) {
+            query.append("order", "asc");
+        }
+    }
 }
 
 pub struct GetFillsResponse(Bytes);
 
 response!(GetFillsResponse, Vec<Fill<'a>>, Vec<FillPartial<'a>>);
 
+/// Every fill between two times, as fetched page by page with
+/// [`GetFills`]. Fills borrow from the underlying responses, so are read
+/// with [`fills`](Self::fills).
+pub struct FillHistory {
+    pages: Vec<GetFillsResponse>,
+}
+
+impl FillHistory {
+    /// All fills, newest first, with fills repeated across pages
+    /// removed.
+    pub fn fills(&self) -> Result<Vec<Fill<'_>>, Error> {
+        let mut seen = HashSet::new();
+        let mut fills = Vec::new();
+
+        for page in &self.pages {
+            for fill in page.deserialize()? {
+                if seen.insert(fill.id) {
+                    fills.push(fill);
+                }
+            }
+        }
+
+        Ok(fills)
+    }
+}
+
+/// Walk [`GetFills`] backwards from `end` to `start`, moving `end_time`
+/// to the oldest fill seen. When a page has nothing new, e.g. because
+/// all its fills share one millisecond, `end_time` steps back past it,
+/// until a page comes back empty or `start` is passed. Fills beyond a
+/// full page that share a single millisecond can't be reached this way
+/// as `GetFills` has no other cursor.
+#[cfg_attr(not(feature = "client"), allow(dead_code))]
+pub(crate) async fn page_fills<'a, E>(
+    executor: &E,
+    market: Option<&'a str>,
+    start: UnixTimestamp,
+    end: UnixTimestamp,
+    timeout: Option<Duration>,
+) -> Result<FillHistory, Error>
+where
+    E: AuthExecutor<GetFills<'a>>,
+{
+    let mut seen = HashSet::new();
+    let mut pages = Vec::new();
+    let mut end_time = end;
+
+    loop {
+        let request = GetFills {
+            market,
+            start_time: Some(start),
+            end_time: Some(end_time),
+            order_id: None,
+            order: None,
+        };
+
+        let page = executor.execute(&request, timeout).await?;
+
+        let mut has_new = false;
+        let mut oldest: Option<FtxDateTime> = None;
+
+        for fill in page.deserialize_partial()? {
+            let id = fill
+                .id
+                .deserialize()
+                .map_err(|e| Error::new(ErrorKind::DeserializationFailed).with_source(e))?;
+            let time = fill
+                .time
+                .deserialize()
+                .map_err(|e| Error::new(ErrorKind::DeserializationFailed).with_source(e))?;
+
+            has_new |= seen.insert(id);
+
+            oldest = Some(oldest.map_or(time, |o| o.min(time)));
+        }
+
+        let oldest: UnixTimestamp = match oldest {
+            Some(oldest) => oldest.get().try_into().map_err(|e: InvalidUnixTimestamp| {
+                Error::new(ErrorKind::DeserializationFailed).with_source(e)
+            })?,
+            None => break,
+        };
+
+        let next_end_time = if has_new {
+            pages.push(page);
+            oldest
+        } else {
+            match oldest.get().checked_sub(1) {
+                Some(ts) => UnixTimestamp::new(ts),
+                None => break,
+            }
+        };
+
+        // Stop if the window can't shrink any further.
+        if next_end_time.get() < start.get() || (!has_new && next_end_time.get() >= end_time.get())
+        {
+            break;
+        }
+
+        end_time = next_end_time;
+    }
+
+    Ok(FillHistory { pages })
+}
+
+/// The fills of the order with id `order_id`. Only the `order_id` of
+/// other fills is deserialized.
+pub fn fills_for_order<'a>(
+    fills: &[FillPartial<'a>],
+    order_id: u64,
+) -> Result<Vec<Fill<'a>>, serde_json::Error> {
+    let mut order_fills = Vec::new();
+
+    for fill in fills {
+        if fill.order_id.deserialize()? == order_id {
+            order_fills.push(Fill::try_from(*fill)?);
+        }
+    }
+
+    Ok(order_fills)
+}
+
+/// Whether the fills of `order` found in `fills` add up to the order's
+/// `filled_size`. Fills of other orders are ignored.
+pub fn fills_match_order(fills: &[Fill<'_>], order: &Order<'_>) -> bool {
+    let filled: Decimal = fills
+        .iter()
+        .filter(|f| f.order_id == order.id)
+        .map(|f| f.size)
+        .sum();
+
+    filled == order.filled_size
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "deny-unknown-fields", serde(deny_unknown_fields))]
@@ -105,6 +265,22 @@ pub struct Fill<'a> {
     pub fee_rate: Decimal,
 }
 
+impl<'a> Fill<'a> {
+    /// Whether the fill was in a spot market. Only spot fills have a
+    /// base and quote currency set.
+    pub fn is_spot(&self) -> bool {
+        self.spot_pair().is_some()
+    }
+
+    /// The base and quote currency of a spot fill, `None` for futures.
+    pub fn spot_pair(&self) -> Option<(BaseCurrency<'a>, QuoteCurrency<'a>)> {
+        match (self.base_currency, self.quote_currency) {
+            (Some(base), Some(quote)) => Some((BaseCurrency(base), QuoteCurrency(quote))),
+            _ => None,
+        }
+    }
+}
+
 impl<'a> TryFrom<FillPartial<'a>> for Fill<'a> {
     type Error = serde_json::Error;
 
@@ -165,10 +341,177 @@ pub struct FillPartial<'a> {
 
 #[cfg(test)]
 mod tests {
-    use crate::Response;
+    use async_trait::async_trait;
+    use std::sync::Mutex;
 
     use super::*;
 
+    struct MockExecutor {
+        pages: Mutex<Vec<String>>,
+        end_times: Mutex<Vec<Option<UnixTimestamp>>>,
+    }
+
+    #[async_trait]
+    impl<'a> AuthExecutor<GetFills<'a>> for MockExecutor {
+        async fn execute(
+            &self,
+            request: &GetFills<'a>,
+            _: Option<Duration>,
+        ) -> Result<GetFillsResponse, Error> {
+            self.end_times.lock().unwrap().push(request.end_time);
+
+            let page = self.pages.lock().unwrap().remove(0);
+
+            Ok(GetFillsResponse(page.into()))
+        }
+    }
+
+    fn fill(id: u64, time: &str) -> String {
+        format!(
+            r#"{{
+  "fee": 0.0001,
+  "feeCurrency": "BTC",
+  "feeRate": 0.0002,
+  "future": null,
+  "id": {},
+  "liquidity": "maker",
+  "market": "BTC/USD",
+  "baseCurrency": "BTC",
+  "quoteCurrency": "USD",
+  "orderId": 8436982,
+  "tradeId": 1013913,
+  "price": 4113.5,
+  "side": "buy",
+  "size": 0.5,
+  "time": "{}",
+  "type": "order"
+}}"#,
+            id, time
+        )
+    }
+
+    fn page(fills: &[String]) -> String {
+        format!(r#"{{"success": true, "result": [{}]}}"#, fills.join(","))
+    }
+
+    #[tokio::test]
+    async fn page_fills_until_exhausted() {
+        let t1 = "2019-03-27T19:15:01+00:00";
+        let t2 = "2019-03-27T19:15:02+00:00";
+        let t3 = "2019-03-27T19:15:03+00:00";
+
+        let executor = MockExecutor {
+            pages: Mutex::new(vec![
+                page(&[fill(3, t3), fill(2, t2)]),
+                page(&[fill(2, t2), fill(1, t1)]),
+                page(&[fill(1, t1)]),
+                page(&[]),
+            ]),
+            end_times: Mutex::new(Vec::new()),
+        };
+
+        let start = UnixTimestamp::new(1553714000000);
+        let end = UnixTimestamp::new(1553714200000);
+
+        let history = page_fills(&executor, None, start, end, None).await.unwrap();
+
+        let ids: Vec<u64> = history.fills().unwrap().iter().map(|f| f.id).collect();
+
+        assert_eq!(ids, vec![3, 2, 1]);
+        assert_eq!(
+            executor.end_times.into_inner().unwrap(),
+            vec![
+                Some(end),
+                Some(UnixTimestamp::new(1553714102000)),
+                Some(UnixTimestamp::new(1553714101000)),
+                Some(UnixTimestamp::new(1553714100999)),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn page_fills_past_identical_timestamps() {
+        let t1 = "2019-03-27T19:15:01+00:00";
+        let t2 = "2019-03-27T19:15:02+00:00";
+
+        // The first page is full of fills from the same millisecond, so
+        // moving `end_time` to the oldest of them returns it again.
+        let executor = MockExecutor {
+            pages: Mutex::new(vec![
+                page(&[fill(3, t2), fill(2, t2)]),
+                page(&[fill(3, t2), fill(2, t2)]),
+                page(&[fill(1, t1)]),
+                page(&[fill(1, t1)]),
+                page(&[]),
+            ]),
+            end_times: Mutex::new(Vec::new()),
+        };
+
+        let start = UnixTimestamp::new(1553714000000);
+        let end = UnixTimestamp::new(1553714200000);
+
+        let history = page_fills(&executor, None, start, end, None).await.unwrap();
+
+        let ids: Vec<u64> = history.fills().unwrap().iter().map(|f| f.id).collect();
+
+        assert_eq!(ids, vec![3, 2, 1]);
+        assert_eq!(
+            executor.end_times.into_inner().unwrap(),
+            vec![
+                Some(end),
+                Some(UnixTimestamp::new(1553714102000)),
+                Some(UnixTimestamp::new(1553714101999)),
+                Some(UnixTimestamp::new(1553714101000)),
+                Some(UnixTimestamp::new(1553714100999)),
+            ]
+        );
+    }
+
+    #[test]
+    fn order_fills() {
+        let t1 = "2019-03-27T19:15:01+00:00";
+        let t2 = "2019-03-27T19:15:02+00:00";
+
+        let other_order = fill(3, t2).replace("8436982", "8436983");
+        let response = GetFillsResponse(page(&[fill(1, t1), other_order, fill(2, t2)]).into());
+
+        let fills = fills_for_order(&response.deserialize_partial().unwrap(), 8436982).unwrap();
+
+        let ids: Vec<u64> = fills.iter().map(|f| f.id).collect();
+        assert_eq!(ids, vec![1, 2]);
+
+        let order: Order<'_> = serde_json::from_str(
+            r#"
+{
+  "createdAt": "2019-03-27T19:15:00+00:00",
+  "filledSize": 1,
+  "future": null,
+  "id": 8436982,
+  "market": "BTC/USD",
+  "price": 4113.5,
+  "avgFillPrice": 4113.5,
+  "remainingSize": 0,
+  "side": "buy",
+  "size": 1,
+  "status": "closed",
+  "type": "limit",
+  "reduceOnly": false,
+  "ioc": false,
+  "postOnly": false,
+  "clientId": null,
+  "liquidation": false
+}
+"#,
+        )
+        .unwrap();
+
+        let all_fills = response.deserialize().unwrap();
+
+        assert!(fills_match_order(&fills, &order));
+        assert!(fills_match_order(&all_fills, &order));
+        assert!(!fills_match_order(&fills[..1], &order));
+    }
+
     #[test]
     fn get_fills() {
         let json = r#"
@@ -192,6 +535,24 @@ mod tests {
       "size": 9587,
       "time": "2019-03-27T19:15:10.204619+00:00",
       "type": "order"
+    },
+    {
+      "fee": 0.0001,
+      "feeCurrency": "BTC",
+      "feeRate": 0.0002,
+      "future": null,
+      "id": 11216,
+      "liquidity": "maker",
+      "market": "BTC/USD",
+      "baseCurrency": "BTC",
+      "quoteCurrency": "USD",
+      "orderId": 8436982,
+      "tradeId": 1013913,
+      "price": 4113.5,
+      "side": "buy",
+      "size": 0.5,
+      "time": "2019-03-27T19:16:10.204619+00:00",
+      "type": "order"
     }
   ]
 }
@@ -206,5 +567,14 @@ mod tests {
             .collect();
 
         assert_eq!(response.deserialize().unwrap(), from_partial);
+
+        assert!(!from_partial[0].is_spot());
+        assert_eq!(from_partial[0].spot_pair(), None);
+
+        assert!(from_partial[1].is_spot());
+        assert_eq!(
+            from_partial[1].spot_pair(),
+            Some((BaseCurrency("BTC"), QuoteCurrency("USD")))
+        );
     }
 }
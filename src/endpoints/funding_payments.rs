@@ -1,14 +1,15 @@
-use std::convert::TryFrom;
+use std::{collections::BTreeMap, convert::TryFrom};
 
 use bytes::Bytes;
-use reqwest::Method;
+use http::Method;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
 
 use crate::{
-    data::{FtxDateTime, UnixTimestamp},
+    data::{FtxDateTime, UnixTimestamp, WindowLength},
     private::Sealed,
-    Json, Request,
+    Json, QueryWriter, Request,
 };
 
 use super::macros::response;
@@ -50,6 +51,18 @@ impl<'a> Request<true> for GetFundingPayments<'a> {
 
         Some(params)
     }
+
+    fn write_query(&self, query: &mut QueryWriter<'_>) {
+        if let Some(future) = self.future {
+            query.append("future", future);
+        }
+        if let Some(start_time) = self.start_time {
+            query.append_uint("start_time", start_time.get());
+        }
+        if let Some(end_time) = self.end_time {
+            query.append_uint("end_time", end_time.get());
+        }
+    }
 }
 
 pub struct GetFundingPaymentsResponse(Bytes);
@@ -100,6 +113,35 @@ pub struct FundingPaymentPartial<'a> {
     pub time: Json<'a, FtxDateTime>,
 }
 
+/// Sum payments into consecutive periods of length `period`, aligned to
+/// the unix epoch. Returns `(period start, net payment)` pairs ordered by
+/// time, periods without payments are omitted.
+pub fn funding_by_period(
+    payments: &[FundingPaymentPartial<'_>],
+    period: WindowLength,
+) -> Result<Vec<(FtxDateTime, Decimal)>, serde_json::Error> {
+    let period_secs = period.to_secs() as i64;
+
+    let mut totals = BTreeMap::new();
+
+    for payment in payments {
+        let ts = payment.time.deserialize()?.get().unix_timestamp();
+        let start = ts - ts.rem_euclid(period_secs);
+
+        *totals.entry(start).or_insert(Decimal::ZERO) += payment.payment.deserialize()?;
+    }
+
+    Ok(totals
+        .into_iter()
+        .map(|(start, total)| {
+            let start = OffsetDateTime::from_unix_timestamp(start)
+                .expect("period start is no earlier than a valid payment");
+
+            (start.into(), total)
+        })
+        .collect())
+}
+
 #[cfg(test)]
 mod tests {
     use crate::Response;
@@ -134,4 +176,54 @@ mod tests {
 
         assert_eq!(response.deserialize().unwrap(), from_partial);
     }
+
+    #[test]
+    fn funding_by_day() {
+        let json = r#"
+{
+  "success": true,
+  "result": [
+    {
+      "future": "ETH-PERP",
+      "id": 33832,
+      "payment": -0.02,
+      "time": "2019-05-16T01:00:00+00:00",
+      "rate": -0.0001
+    },
+    {
+      "future": "ETH-PERP",
+      "id": 33831,
+      "payment": 0.01,
+      "time": "2019-05-15T19:00:00+00:00",
+      "rate": 0.0001
+    },
+    {
+      "future": "BTC-PERP",
+      "id": 33830,
+      "payment": 0.0441342,
+      "time": "2019-05-15T18:00:00+00:00",
+      "rate": 0.0001
+    }
+  ]
+}
+"#;
+        let response = GetFundingPaymentsResponse(json.as_bytes().into());
+        let payments = response.deserialize_partial().unwrap();
+
+        let day = |ts| OffsetDateTime::from_unix_timestamp(ts).unwrap();
+
+        let by_day: Vec<_> = funding_by_period(&payments, WindowLength::from_secs(86400).unwrap())
+            .unwrap()
+            .into_iter()
+            .map(|(start, total)| (start.get(), total))
+            .collect();
+
+        assert_eq!(
+            by_day,
+            [
+                (day(1557878400), Decimal::new(541342, 7)),
+                (day(1557964800), Decimal::new(-2, 2)),
+            ]
+        );
+    }
 }
@@ -1,17 +1,24 @@
-use std::{borrow::Cow, convert::TryFrom};
+use std::{
+    borrow::Cow, cmp::Reverse, collections::HashMap, convert::TryFrom, error::Error as StdError,
+    fmt,
+};
 
 use bytes::Bytes;
-use reqwest::Method;
+use http::Method;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use time::{Duration, OffsetDateTime};
 
 use crate::{
     data::{FtxDateTime, FutureType, UnixTimestamp},
+    error::{Error, ErrorKind},
     private::Sealed,
-    Json, OptJson, Request,
+    Json, OptJson, QueryWriter, Request, Response,
 };
 
-use super::macros::response;
+use super::{macros::response, markets::Market, FtxResponse};
+
+const HOURS_PER_YEAR: u32 = 24 * 365;
 
 macro_rules! get_future_path {
     () => {
@@ -51,7 +58,7 @@ impl Sealed for GetFutures {}
 impl Request<false> for GetFutures {
     const PATH: &'static str = "/futures";
 
-    const METHOD: reqwest::Method = Method::GET;
+    const METHOD: Method = Method::GET;
 
     type Response = GetFuturesResponse;
 }
@@ -61,6 +68,25 @@ pub struct GetFuturesResponse(Bytes);
 
 response!(GetFuturesResponse, Vec<Future<'a>>, Vec<FuturePartial<'a>>);
 
+impl GetFuturesResponse {
+    /// Only perpetual futures.
+    pub fn perpetuals_only(&self) -> Result<Vec<Future<'_>>, Error> {
+        self.filter_by_perpetual(true)
+    }
+
+    /// Only futures which aren't perpetual, e.g. quarterly or MOVE
+    /// contracts.
+    pub fn dated_only(&self) -> Result<Vec<Future<'_>>, Error> {
+        self.filter_by_perpetual(false)
+    }
+
+    fn filter_by_perpetual(&self, perpetual: bool) -> Result<Vec<Future<'_>>, Error> {
+        let mut futures = self.deserialize()?;
+        futures.retain(|f| (f.group == FutureGroup::Perpetual) == perpetual);
+        Ok(futures)
+    }
+}
+
 /// Retrieve information on a single future.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct GetFuture<'a> {
@@ -72,7 +98,7 @@ impl<'a> Sealed for GetFuture<'a> {}
 impl<'a> Request<false> for GetFuture<'a> {
     const PATH: &'static str = get_future_path!();
 
-    const METHOD: reqwest::Method = Method::GET;
+    const METHOD: Method = Method::GET;
 
     type Response = GetFutureResponse;
 
@@ -97,7 +123,7 @@ impl<'a> Sealed for GetFutureStats<'a> {}
 impl<'a> Request<false> for GetFutureStats<'a> {
     const PATH: &'static str = get_future_stats_path!();
 
-    const METHOD: reqwest::Method = Method::GET;
+    const METHOD: Method = Method::GET;
 
     type Response = GetFutureStatsResponse;
 
@@ -147,6 +173,18 @@ impl<'a> Request<false> for GetFundingRates<'a> {
 
         Some(params)
     }
+
+    fn write_query(&self, query: &mut QueryWriter<'_>) {
+        if let Some(future) = self.perpetual {
+            query.append("future", future);
+        }
+        if let Some(start_time) = self.start_time {
+            query.append_uint("start_time", start_time.get());
+        }
+        if let Some(end_time) = self.end_time {
+            query.append_uint("end_time", end_time.get());
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -167,7 +205,7 @@ impl Sealed for GetExpiredFutures {}
 impl Request<false> for GetExpiredFutures {
     const PATH: &'static str = "/expired_futures";
 
-    const METHOD: reqwest::Method = Method::GET;
+    const METHOD: Method = Method::GET;
 
     type Response = GetExpiredFuturesResponse;
 }
@@ -181,6 +219,25 @@ response!(
     Vec<ExpiredFuturePartial<'a>>
 );
 
+impl GetExpiredFuturesResponse {
+    /// Iterate over the expired futures, deserializing each one only when
+    /// it's reached. The list is long, so this allows filtering entries
+    /// without deserializing all of them. The array itself is still split
+    /// up front into one borrowed raw JSON slice per future, only the
+    /// element bodies are deserialized lazily.
+    pub fn iter_partial(
+        &self,
+    ) -> Result<impl Iterator<Item = Result<ExpiredFuturePartial<'_>, Error>> + '_, Error> {
+        let futures: Vec<Json<'_, ExpiredFuturePartial<'_>>> =
+            FtxResponse::try_from(self.0.as_ref())?.deserialize()?;
+
+        Ok(futures.into_iter().map(|f| {
+            f.deserialize()
+                .map_err(|e| Error::new(ErrorKind::DeserializationFailed).with_source(e))
+        }))
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "deny-unknown-fields", serde(deny_unknown_fields))]
@@ -222,6 +279,144 @@ pub struct Future<'a> {
     pub move_start: Option<FtxDateTime>,
 }
 
+/// The measurement window of a MOVE contract, which settles on how far
+/// the underlying moves between `move_start` and `expiry`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MoveContractInfo {
+    pub move_start: FtxDateTime,
+    pub expiry: Option<FtxDateTime>,
+}
+
+impl MoveContractInfo {
+    /// Time elapsed in the window at `now`, zero if the window hasn't
+    /// started yet.
+    pub fn elapsed_at(&self, now: OffsetDateTime) -> Duration {
+        (now - self.move_start.get()).max(Duration::ZERO)
+    }
+
+    /// Time elapsed in the window so far.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed_at(OffsetDateTime::now_utc())
+    }
+
+    /// The total length of the window, if the expiry is known.
+    pub fn window_length(&self) -> Option<Duration> {
+        self.expiry
+            .map(|expiry| expiry.get() - self.move_start.get())
+    }
+}
+
+/// A [`Future`] joined with the [`Market`] of the same name, giving access
+/// to market-only fields such as `quote_volume_24h`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EnrichedFuture<'a> {
+    pub future: Future<'a>,
+    pub market: Market<'a>,
+}
+
+#[derive(Debug)]
+pub struct BasisError {
+    mark: Decimal,
+    index: Decimal,
+}
+
+impl fmt::Display for BasisError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "failed to compute basis for mark {} and index {}",
+            self.mark, self.index
+        )
+    }
+}
+
+impl StdError for BasisError {}
+
+/// Join futures with their markets by name, e.g. the results of
+/// [`GetFutures`] and [`GetMarkets`](super::markets::GetMarkets). Futures
+/// without a matching market are skipped.
+pub fn enrich_futures<'a>(
+    futures: &[Future<'a>],
+    markets: &[Market<'a>],
+) -> Vec<EnrichedFuture<'a>> {
+    let markets: HashMap<&str, &Market<'a>> = markets.iter().map(|m| (m.name, m)).collect();
+
+    futures
+        .iter()
+        .filter_map(|future| {
+            markets.get(future.name).map(|market| EnrichedFuture {
+                future: *future,
+                market: **market,
+            })
+        })
+        .collect()
+}
+
+/// Sort futures by 24h USD volume, highest first.
+pub fn sort_futures_by_volume(mut futures: Vec<Future<'_>>) -> Vec<Future<'_>> {
+    futures.sort_by_key(|f| Reverse(f.volume_usd_24h));
+    futures
+}
+
+/// Sort futures by USD open interest, highest first.
+pub fn sort_futures_by_open_interest(mut futures: Vec<Future<'_>>) -> Vec<Future<'_>> {
+    futures.sort_by_key(|f| Reverse(f.open_interest_usd));
+    futures
+}
+
+/// The dated future on `underlying` which expires soonest, e.g. to roll
+/// into. Perpetuals, MOVE and prediction contracts and expired futures
+/// are skipped.
+pub fn front_month<'f, 'a>(futures: &'f [Future<'a>], underlying: &str) -> Option<&'f Future<'a>> {
+    futures
+        .iter()
+        .filter(|f| f.underlying == underlying && f.r#type == FutureType::Future && !f.expired)
+        .filter_map(|f| f.expiry.map(|expiry| (expiry, f)))
+        .min_by_key(|(expiry, _)| *expiry)
+        .map(|(_, f)| f)
+}
+
+/// Parse the underlying from a future's name, e.g. `BTC` from
+/// `BTC-PERP`, `BTC-0325` or `BTC-MOVE-WK-0325`.
+pub fn underlying_from_name(future: &str) -> Option<&str> {
+    match future.split_once('-') {
+        Some((underlying, _)) if !underlying.is_empty() => Some(underlying),
+        _ => None,
+    }
+}
+
+impl<'a> Future<'a> {
+    pub fn is_move(&self) -> bool {
+        self.r#type == FutureType::Move
+    }
+
+    /// The window of a MOVE contract, or `None` for other future types.
+    pub fn move_info(&self) -> Option<MoveContractInfo> {
+        if !self.is_move() {
+            return None;
+        }
+
+        self.move_start.map(|move_start| MoveContractInfo {
+            move_start,
+            expiry: self.expiry,
+        })
+    }
+
+    /// The basis `(mark - index) / index`, or `None` if either price is
+    /// missing.
+    pub fn basis(&self) -> Result<Option<Decimal>, BasisError> {
+        let (mark, index) = match (self.mark, self.index) {
+            (Some(mark), Some(index)) => (mark, index),
+            _ => return Ok(None),
+        };
+
+        mark.checked_sub(index)
+            .and_then(|diff| diff.checked_div(index))
+            .map(Some)
+            .ok_or(BasisError { mark, index })
+    }
+}
+
 impl<'a> TryFrom<FuturePartial<'a>> for Future<'a> {
     type Error = serde_json::Error;
 
@@ -350,6 +545,15 @@ pub struct FutureStats {
     pub open_interest: Decimal,
 }
 
+impl FutureStats {
+    /// Annualised funding rate, as displayed by FTX. `next_funding_rate` is
+    /// an hourly rate that will be paid at `next_funding_time`.
+    pub fn predicted_apr(&self) -> Option<Decimal> {
+        self.next_funding_rate
+            .map(|rate| rate * Decimal::from(HOURS_PER_YEAR))
+    }
+}
+
 impl<'a> TryFrom<FutureStatsPartial<'a>> for FutureStats {
     type Error = serde_json::Error;
 
@@ -395,6 +599,42 @@ pub struct FundingRate<'a> {
     pub time: FtxDateTime,
 }
 
+/// Pick the most recent rate for each of `perps` out of a
+/// [`GetFundingRates`] response covering all perpetuals. Perps with no
+/// rate are left out.
+pub fn latest_rates_for(perps: &[&str], all_rates: &[FundingRate<'_>]) -> HashMap<String, Decimal> {
+    let mut latest: HashMap<&str, &FundingRate<'_>> = HashMap::with_capacity(perps.len());
+
+    for rate in all_rates.iter().filter(|r| perps.contains(&r.future)) {
+        latest
+            .entry(rate.future)
+            .and_modify(|l| {
+                if rate.time > l.time {
+                    *l = rate;
+                }
+            })
+            .or_insert(rate);
+    }
+
+    latest
+        .into_iter()
+        .map(|(future, rate)| (future.to_owned(), rate.rate))
+        .collect()
+}
+
+/// How far a realized funding rate was from its prediction, as
+/// `realized - predicted`, so positive means longs paid more than
+/// expected.
+///
+/// Both rates are hourly, as returned by the exchange, and aren't
+/// comparable with the 8 hour rates quoted by some other venues. The
+/// prediction from [`FutureStats::next_funding_rate`] is for the payment
+/// at `next_funding_time`, so should be compared with the
+/// [`FundingRate`] at that time rather than the latest one.
+pub fn funding_prediction_error(predicted: Decimal, realized: Decimal) -> Decimal {
+    realized - predicted
+}
+
 impl<'a> TryFrom<FundingRatePartial<'a>> for FundingRate<'a> {
     type Error = serde_json::Error;
 
@@ -553,14 +793,13 @@ pub struct ExpiredFuturePartial<'a> {
 #[cfg(test)]
 mod tests {
     use std::convert::TryInto;
+    use time::macros::datetime;
 
-    use crate::Response;
+    use crate::{error::RejectionReason, Response};
 
     use super::*;
 
-    #[test]
-    fn get_futures() {
-        let json = r#"
+    const GET_FUTURES_JSON: &str = r#"
 {
   "success": true,
   "result": [
@@ -604,6 +843,10 @@ mod tests {
   ]
 }
 "#;
+
+    #[test]
+    fn get_futures() {
+        let json = GET_FUTURES_JSON;
         let response = GetFuturesResponse(json.as_bytes().into());
 
         let from_partial: Vec<Future<'_>> = response
@@ -614,6 +857,284 @@ mod tests {
             .collect();
 
         assert_eq!(response.deserialize().unwrap(), from_partial);
+
+        let future = from_partial[0];
+        assert!(future.is_move());
+
+        let info = future.move_info().unwrap();
+        assert_eq!(info.move_start.get(), datetime!(2022-04-02 00:00 UTC));
+        assert_eq!(info.window_length(), Some(Duration::DAY));
+        assert_eq!(
+            info.elapsed_at(datetime!(2022-04-02 06:00 UTC)),
+            Duration::hours(6)
+        );
+        assert_eq!(
+            info.elapsed_at(datetime!(2022-04-01 23:00 UTC)),
+            Duration::ZERO
+        );
+    }
+
+    const BTC_PERP_JSON: &str = r#"
+{
+  "name": "BTC-PERP",
+  "underlying": "BTC",
+  "description": "Bitcoin Perpetual Futures",
+  "type": "perpetual",
+  "expiry": null,
+  "perpetual": true,
+  "expired": false,
+  "enabled": true,
+  "postOnly": false,
+  "closeOnly": false,
+  "priceIncrement": 1,
+  "sizeIncrement": 0.0001,
+  "last": 46204,
+  "bid": 46203,
+  "ask": 46204,
+  "index": 46088.731248179,
+  "mark": 46204,
+  "imfFactor": 0.002,
+  "imfWeight": 1,
+  "mmfWeight": 1,
+  "lowerBound": 43786,
+  "upperBound": 48394,
+  "underlyingDescription": "Bitcoin",
+  "expiryDescription": "Perpetual",
+  "moveStart": null,
+  "marginPrice": 46203,
+  "positionLimitWeight": 1,
+  "group": "perpetual",
+  "change1h": 0.0012,
+  "change24h": -0.0122,
+  "changeBod": -0.0035,
+  "volumeUsd24h": 2389473120.8367,
+  "volume": 51562.6702,
+  "openInterest": 36241.0466,
+  "openInterestUsd": 1674468567.5
+}
+"#;
+
+    #[test]
+    fn future_basis() {
+        let future: Future<'_> = serde_json::from_str(BTC_PERP_JSON).unwrap();
+
+        let basis = future.basis().unwrap().unwrap();
+
+        assert_eq!(basis.round_dp(10), Decimal::new(25010181, 10));
+
+        let future = Future {
+            index: None,
+            ..future
+        };
+
+        assert_eq!(future.basis().unwrap(), None);
+    }
+
+    #[test]
+    fn perpetual_and_dated() {
+        let json = GET_FUTURES_JSON.replacen(
+            r#""result": ["#,
+            &format!(r#""result": [{},"#, BTC_PERP_JSON),
+            1,
+        );
+        let response = GetFuturesResponse(json.into());
+
+        let perps = response.perpetuals_only().unwrap();
+        assert_eq!(perps.len(), 1);
+        assert_eq!(perps[0].name, "BTC-PERP");
+
+        let dated = response.dated_only().unwrap();
+        assert_eq!(dated.len(), 1);
+        assert_eq!(dated[0].name, "BTC-MOVE-0402");
+    }
+
+    #[test]
+    fn sort_futures() {
+        let json = r#"
+{
+  "success": true,
+  "result": [
+    {
+      "name": "BTC-MOVE-0402",
+      "underlying": "BTC",
+      "description": "Bitcoin MOVE 2022-04-02 Contracts",
+      "type": "move",
+      "expiry": "2022-04-03T00:00:00+00:00",
+      "perpetual": false,
+      "expired": false,
+      "enabled": true,
+      "postOnly": false,
+      "closeOnly": false,
+      "priceIncrement": 1,
+      "sizeIncrement": 0.0001,
+      "last": 299,
+      "bid": 294,
+      "ask": 304,
+      "index": 46088.731248179,
+      "mark": 299,
+      "imfFactor": 0.002,
+      "imfWeight": 1,
+      "mmfWeight": 1,
+      "lowerBound": 1,
+      "upperBound": 4881,
+      "underlyingDescription": "Bitcoin",
+      "expiryDescription": "Today",
+      "moveStart": "2022-04-02T00:00:00+00:00",
+      "marginPrice": 46088.731248179,
+      "positionLimitWeight": 2,
+      "group": "daily",
+      "change1h": 0.31140350877192985,
+      "change24h": -0.6210392902408112,
+      "changeBod": -0.6238993710691824,
+      "volumeUsd24h": 361892.0658,
+      "volume": 566.0078,
+      "openInterest": 507.2044,
+      "openInterestUsd": 151654.1156
+    },
+    {
+      "name": "BTC-MOVE-0403",
+      "underlying": "BTC",
+      "description": "Bitcoin MOVE 2022-04-03 Contracts",
+      "type": "move",
+      "expiry": "2022-04-04T00:00:00+00:00",
+      "perpetual": false,
+      "expired": false,
+      "enabled": true,
+      "postOnly": false,
+      "closeOnly": false,
+      "priceIncrement": 1,
+      "sizeIncrement": 0.0001,
+      "last": 850,
+      "bid": 846,
+      "ask": 852,
+      "index": 46088.731248179,
+      "mark": 850,
+      "imfFactor": 0.002,
+      "imfWeight": 1,
+      "mmfWeight": 1,
+      "lowerBound": 1,
+      "upperBound": 5432,
+      "underlyingDescription": "Bitcoin",
+      "expiryDescription": "Tomorrow",
+      "moveStart": "2022-04-03T00:00:00+00:00",
+      "marginPrice": 46088.731248179,
+      "positionLimitWeight": 2,
+      "group": "daily",
+      "change1h": 0.0143,
+      "change24h": 0.0821,
+      "changeBod": 0.0402,
+      "volumeUsd24h": 120345.5,
+      "volume": 141.583,
+      "openInterest": 612.0013,
+      "openInterestUsd": 520201.105
+    }
+  ]
+}
+"#;
+        let futures = GetFuturesResponse(json.as_bytes().into());
+        let futures = futures.deserialize().unwrap();
+
+        // Reversed so the input isn't already in volume order.
+        let by_volume = sort_futures_by_volume(futures.iter().rev().copied().collect());
+
+        assert_eq!(by_volume[0].name, "BTC-MOVE-0402");
+        assert_eq!(by_volume[1].name, "BTC-MOVE-0403");
+
+        let by_open_interest = sort_futures_by_open_interest(futures);
+
+        assert_eq!(by_open_interest[0].name, "BTC-MOVE-0403");
+        assert_eq!(by_open_interest[1].name, "BTC-MOVE-0402");
+    }
+
+    #[test]
+    fn front_month_future() {
+        let perp: Future<'_> = serde_json::from_str(BTC_PERP_JSON).unwrap();
+
+        let dated = |name, expiry: OffsetDateTime| Future {
+            name,
+            r#type: FutureType::Future,
+            perpetual: false,
+            expiry: Some(expiry.into()),
+            ..perp
+        };
+
+        let futures = vec![
+            perp,
+            dated("BTC-0930", datetime!(2022-09-30 03:00 UTC)),
+            Future {
+                expired: true,
+                ..dated("BTC-0325", datetime!(2022-03-25 03:00 UTC))
+            },
+            dated("BTC-0624", datetime!(2022-06-24 03:00 UTC)),
+            Future {
+                r#type: FutureType::Move,
+                ..dated("BTC-MOVE-0402", datetime!(2022-04-03 00:00 UTC))
+            },
+            Future {
+                underlying: "ETH",
+                ..dated("ETH-0401", datetime!(2022-04-01 03:00 UTC))
+            },
+        ];
+
+        assert_eq!(front_month(&futures, "BTC").unwrap().name, "BTC-0624");
+        assert_eq!(front_month(&futures, "ETH").unwrap().name, "ETH-0401");
+        assert_eq!(front_month(&futures, "SOL"), None);
+        assert_eq!(front_month(&futures[..1], "BTC"), None);
+    }
+
+    #[test]
+    fn enrich_futures_by_name() {
+        let future: Future<'_> = serde_json::from_str(BTC_PERP_JSON).unwrap();
+
+        let market_json = r#"
+{
+  "name": "MARKET",
+  "baseCurrency": null,
+  "quoteCurrency": null,
+  "quoteVolume24h": 28914.76,
+  "change1h": 0.012,
+  "change24h": 0.0299,
+  "changeBod": 0.0156,
+  "highLeverageFeeExempt": false,
+  "minProvideSize": 0.001,
+  "type": "future",
+  "underlying": "BTC",
+  "enabled": true,
+  "ask": 46204,
+  "bid": 46203,
+  "last": 46204,
+  "postOnly": false,
+  "price": 46204,
+  "priceIncrement": 1,
+  "sizeIncrement": 0.0001,
+  "restricted": false,
+  "volumeUsd24h": 28914.76,
+  "largeOrderThreshold": 5000.0,
+  "isEtfMarket": false
+}
+"#;
+
+        let eth_json = market_json.replace("MARKET", "ETH-PERP");
+        let btc_json = market_json.replace("MARKET", "BTC-PERP");
+
+        let markets: [Market<'_>; 2] = [
+            serde_json::from_str(&eth_json).unwrap(),
+            serde_json::from_str(&btc_json).unwrap(),
+        ];
+
+        let enriched = enrich_futures(&[future], &markets);
+
+        assert_eq!(
+            enriched,
+            vec![EnrichedFuture {
+                future,
+                market: markets[1]
+            }]
+        );
+        assert_eq!(
+            enriched[0].market.quote_volume_24h,
+            Some(Decimal::new(2891476, 2))
+        );
     }
 
     #[test]
@@ -688,6 +1209,8 @@ mod tests {
         let from_partial: FutureStats = response.deserialize_partial().unwrap().try_into().unwrap();
 
         assert_eq!(response.deserialize().unwrap(), from_partial);
+
+        assert_eq!(from_partial.predicted_apr(), Some(Decimal::new(219, 2)));
     }
 
     #[test]
@@ -717,8 +1240,93 @@ mod tests {
     }
 
     #[test]
-    fn get_expired_futures() {
+    fn parse_underlying() {
+        assert_eq!(underlying_from_name("BTC-PERP"), Some("BTC"));
+        assert_eq!(underlying_from_name("1INCH-0325"), Some("1INCH"));
+        assert_eq!(underlying_from_name("BTC-MOVE-WK-0325"), Some("BTC"));
+        assert_eq!(underlying_from_name("BTC/USD"), None);
+        assert_eq!(underlying_from_name("-PERP"), None);
+    }
+
+    #[test]
+    fn prediction_error() {
+        assert_eq!(
+            funding_prediction_error(Decimal::new(25, 6), Decimal::new(31, 6)),
+            Decimal::new(6, 6)
+        );
+        assert_eq!(
+            funding_prediction_error(Decimal::new(1, 5), Decimal::new(-1, 5)),
+            Decimal::new(-2, 5)
+        );
+    }
+
+    #[test]
+    fn latest_rates() {
         let json = r#"
+{
+  "success": true,
+  "result": [
+    {
+      "future": "BTC-PERP",
+      "rate": 0.0025,
+      "time": "2019-06-02T08:00:00+00:00"
+    },
+    {
+      "future": "ETH-PERP",
+      "rate": -0.0001,
+      "time": "2019-06-02T09:00:00+00:00"
+    },
+    {
+      "future": "BTC-PERP",
+      "rate": 0.0031,
+      "time": "2019-06-02T09:00:00+00:00"
+    },
+    {
+      "future": "SOL-PERP",
+      "rate": 0.0004,
+      "time": "2019-06-02T09:00:00+00:00"
+    },
+    {
+      "future": "ETH-PERP",
+      "rate": 0.0002,
+      "time": "2019-06-02T08:00:00+00:00"
+    }
+  ]
+}
+"#;
+        let response = GetFundingRatesResponse(json.as_bytes().into());
+
+        let rates = latest_rates_for(&["BTC-PERP", "ETH-PERP"], &response.deserialize().unwrap());
+
+        assert_eq!(rates.len(), 2);
+        assert_eq!(rates["BTC-PERP"], Decimal::new(31, 4));
+        assert_eq!(rates["ETH-PERP"], Decimal::new(-1, 4));
+    }
+
+    #[test]
+    fn unknown_future() {
+        let response = GetFutureStatsResponse(
+            r#"{"success": false, "error": "No such future: BTC-0325"}"#
+                .as_bytes()
+                .into(),
+        );
+
+        let err = response.into_result().unwrap_err();
+        assert_eq!(err.rejection_reason(), Some(RejectionReason::UnknownFuture));
+        assert!(!err.is_retryable());
+
+        let response = GetFutureStatsResponse(
+            r#"{"success": false, "error": "Please retry request"}"#
+                .as_bytes()
+                .into(),
+        );
+
+        let err = response.into_result().unwrap_err();
+        assert_eq!(err.rejection_reason(), None);
+        assert!(err.is_retryable());
+    }
+
+    const GET_EXPIRED_FUTURES_JSON: &str = r#"
 {
   "success": true,
   "result": [
@@ -756,6 +1364,10 @@ mod tests {
   ]
 }
 "#;
+
+    #[test]
+    fn get_expired_futures() {
+        let json = GET_EXPIRED_FUTURES_JSON;
         let response = GetExpiredFuturesResponse(json.as_bytes().into());
 
         let from_partial: Vec<ExpiredFuture<'_>> = response
@@ -767,4 +1379,63 @@ mod tests {
 
         assert_eq!(response.deserialize().unwrap(), from_partial);
     }
+
+    const ETH_0325_JSON: &str = r#"
+{
+  "name": "ETH-0325",
+  "underlying": "ETH",
+  "description": "Ethereum March 2022 Futures",
+  "type": "future",
+  "expiry": "2022-03-25T03:00:00+00:00",
+  "perpetual": false,
+  "expired": true,
+  "enabled": false,
+  "postOnly": false,
+  "closeOnly": false,
+  "priceIncrement": 0.1,
+  "sizeIncrement": 0.001,
+  "last": null,
+  "bid": null,
+  "ask": null,
+  "index": 3095.95,
+  "indexAdjustment": null,
+  "mark": 3095.95,
+  "imfFactor": 0.001,
+  "imfWeight": 1,
+  "mmfWeight": 1,
+  "lowerBound": 2940.9,
+  "upperBound": 3251.1,
+  "underlyingDescription": "Ethereum",
+  "expiryDescription": "March 2022",
+  "moveStart": null,
+  "marginPrice": 3095.95,
+  "positionLimitWeight": 20,
+  "group": "quarterly"
+}
+"#;
+
+    #[test]
+    fn iter_expired_futures() {
+        let json = GET_EXPIRED_FUTURES_JSON.replacen(
+            r#""result": ["#,
+            &format!(r#""result": [{},"#, ETH_0325_JSON),
+            1,
+        );
+        let response = GetExpiredFuturesResponse(json.into());
+
+        let quarterly: Vec<ExpiredFuture<'_>> = response
+            .iter_partial()
+            .unwrap()
+            .map(Result::unwrap)
+            .filter(|p| p.group.deserialize().unwrap() == FutureGroup::Quarterly)
+            .map(|p| ExpiredFuture::try_from(p).unwrap())
+            .collect();
+
+        assert_eq!(quarterly.len(), 1);
+        assert_eq!(quarterly[0].name, "ETH-0325");
+        assert_eq!(
+            response.iter_partial().unwrap().count(),
+            response.deserialize().unwrap().len()
+        );
+    }
 }
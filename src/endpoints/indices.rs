@@ -1,5 +1,5 @@
 use bytes::Bytes;
-use reqwest::Method;
+use http::Method;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::{borrow::Cow, collections::HashMap, convert::TryFrom};
@@ -8,8 +8,9 @@ use crate::{
     data::{
         BaseCurrency, Exchange, FtxDateTime, QuoteCurrency, Underlying, UnixTimestamp, WindowLength,
     },
+    error::Error,
     private::Sealed,
-    Json, QueryParams, Request,
+    Json, QueryParams, QueryWriter, Request, Response,
 };
 
 use super::macros::response;
@@ -43,7 +44,7 @@ impl<'a> Sealed for GetWeights<'a> {}
 impl<'a> Request<false> for GetWeights<'a> {
     const PATH: &'static str = get_weights_path!();
 
-    const METHOD: reqwest::Method = Method::GET;
+    const METHOD: Method = Method::GET;
 
     type Response = GetWeightsResponse;
 
@@ -98,6 +99,17 @@ impl<'a> Request<false> for GetCandles<'a> {
 
         Some(params)
     }
+
+    fn write_query(&self, query: &mut QueryWriter<'_>) {
+        query.append_uint("resolution", self.resolution.to_secs());
+
+        if let Some(start_time) = self.start_time {
+            query.append_uint("start_time", start_time.get());
+        }
+        if let Some(end_time) = self.end_time {
+            query.append_uint("end_time", end_time.get());
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -116,7 +128,7 @@ impl<'a> Sealed for GetConstituents<'a> {}
 impl<'a> Request<false> for GetConstituents<'a> {
     const PATH: &'static str = get_constituents_path!();
 
-    const METHOD: reqwest::Method = Method::GET;
+    const METHOD: Method = Method::GET;
 
     type Response = GetConstituentsResponse;
 
@@ -137,6 +149,36 @@ response!(
     Vec<(Exchange<'a>, BaseCurrency<'a>, QuoteCurrency<'a>)>
 );
 
+impl GetConstituentsResponse {
+    /// The response's constituents as named structs rather than tuples.
+    pub fn constituents(&self) -> Result<Vec<IndexConstituent<'_>>, Error> {
+        self.deserialize()
+            .map(|c| c.into_iter().map(IndexConstituent::from).collect())
+    }
+}
+
+/// A named form of the `(exchange, base, quote)` tuples returned by
+/// [`GetConstituents`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct IndexConstituent<'a> {
+    #[serde(borrow)]
+    pub exchange: Exchange<'a>,
+    #[serde(borrow)]
+    pub base: BaseCurrency<'a>,
+    #[serde(borrow)]
+    pub quote: QuoteCurrency<'a>,
+}
+
+impl<'a> From<(Exchange<'a>, BaseCurrency<'a>, QuoteCurrency<'a>)> for IndexConstituent<'a> {
+    fn from((exchange, base, quote): (Exchange<'a>, BaseCurrency<'a>, QuoteCurrency<'a>)) -> Self {
+        Self {
+            exchange,
+            base,
+            quote,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "deny-unknown-fields", serde(deny_unknown_fields))]
@@ -187,8 +229,6 @@ pub struct CandlePartial<'a> {
 
 #[cfg(test)]
 mod tests {
-    use crate::Response;
-
     use super::*;
 
     #[test]
@@ -263,5 +303,27 @@ mod tests {
             response.deserialize_partial().unwrap();
 
         assert_eq!(response.deserialize().unwrap(), from_partial);
+
+        let constituents = response.constituents().unwrap();
+
+        assert_eq!(
+            constituents[1],
+            IndexConstituent {
+                exchange: Exchange("bitstamp"),
+                base: BaseCurrency("BTC"),
+                quote: QuoteCurrency("USD"),
+            }
+        );
+
+        let serialized = serde_json::to_string(&constituents[0]).unwrap();
+
+        assert_eq!(
+            serialized,
+            r#"{"exchange":"binance","base":"BTC","quote":"TUSD"}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<IndexConstituent<'_>>(&serialized).unwrap(),
+            constituents[0]
+        );
     }
 }
@@ -1,16 +1,24 @@
 use bytes::Bytes;
-use reqwest::Method;
-use rust_decimal::Decimal;
+use http::Method;
+use rust_decimal::{prelude::ToPrimitive, Decimal};
 use serde::{Deserialize, Serialize};
-use std::{borrow::Cow, convert::TryFrom, num::NonZeroU8};
+use std::{
+    borrow::Cow, cmp::Reverse, collections::HashSet, convert::TryFrom, error::Error as StdError,
+    fmt, num::NonZeroU8,
+};
+use time::{Date, Duration, OffsetDateTime};
 
 use crate::{
-    data::{FtxDateTime, FutureType, Side, UnixTimestamp, WindowLength},
+    data::{
+        FtxDateTime, FutureType, InvalidUnixTimestamp, OrderSize, PositiveDecimal, Side,
+        TryFromDecimalError, UnixTimestamp, WindowLength,
+    },
+    error::Error,
     private::Sealed,
-    Json, OptJson, QueryParams, Request,
+    Json, OptJson, QueryParams, QueryWriter, Request, Response,
 };
 
-use super::macros::response;
+use super::{macros::response, orders::PlaceOrder};
 
 macro_rules! get_market_path {
     () => {
@@ -53,15 +61,40 @@ pub enum MarketType {
 pub struct BookDepth(u8);
 
 impl BookDepth {
+    pub const MAX: u8 = 100;
+
     pub fn new(depth: NonZeroU8) -> Option<Self> {
-        if depth.get() > 100 {
-            None
+        Self::try_new(depth).ok()
+    }
+
+    /// As [`new`](Self::new), but reports the rejected depth.
+    pub fn try_new(depth: NonZeroU8) -> Result<Self, BookDepthError> {
+        if depth.get() > Self::MAX {
+            Err(BookDepthError { depth: depth.get() })
         } else {
-            Some(BookDepth(depth.get()))
+            Ok(BookDepth(depth.get()))
         }
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BookDepthError {
+    pub depth: u8,
+}
+
+impl fmt::Display for BookDepthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "book depth of {} is greater than the maximum of {}",
+            self.depth,
+            BookDepth::MAX
+        )
+    }
+}
+
+impl StdError for BookDepthError {}
+
 /// Retrieve info on all markets.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct GetMarkets;
@@ -136,6 +169,16 @@ pub struct GetOrderBookResponse(Bytes);
 
 response!(GetOrderBookResponse, OrderBook, OrderBookPartial<'a>);
 
+impl GetOrderBookResponse {
+    /// Count the number of bid and ask levels, in that order, without
+    /// deserializing any of the prices or sizes.
+    pub fn level_count(&self) -> Result<(usize, usize), Error> {
+        let book = self.deserialize_partial()?;
+
+        Ok((book.bids.len(), book.asks.len()))
+    }
+}
+
 /// Retrieve trades in some time frame for the provided market.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct GetTrades<'a> {
@@ -144,6 +187,21 @@ pub struct GetTrades<'a> {
     pub end_time: Option<UnixTimestamp>,
 }
 
+impl<'a> GetTrades<'a> {
+    /// Request the trades of a whole UTC day, from midnight of `date`
+    /// to midnight of the next day. Fails for dates before the UNIX
+    /// epoch.
+    pub fn for_day(market: &'a str, date: Date) -> Result<Self, InvalidUnixTimestamp> {
+        let start = date.midnight().assume_utc();
+
+        Ok(Self {
+            market,
+            start_time: Some(UnixTimestamp::try_from(start)?),
+            end_time: Some(UnixTimestamp::try_from(start + Duration::DAY)?),
+        })
+    }
+}
+
 impl<'a> Sealed for GetTrades<'a> {}
 
 impl<'a> Request<false> for GetTrades<'a> {
@@ -173,6 +231,15 @@ impl<'a> Request<false> for GetTrades<'a> {
 
         Some(params)
     }
+
+    fn write_query(&self, query: &mut QueryWriter<'_>) {
+        if let Some(start_time) = self.start_time {
+            query.append_uint("start_time", start_time.get());
+        }
+        if let Some(end_time) = self.end_time {
+            query.append_uint("end_time", end_time.get());
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -190,6 +257,64 @@ pub struct GetCandles<'a> {
     pub end_time: Option<UnixTimestamp>,
 }
 
+impl<'a> GetCandles<'a> {
+    /// Request the last `n` candles up to now.
+    pub fn last_n(market: &'a str, resolution: WindowLength, n: u32) -> Self {
+        Self::last_n_until(market, resolution, n, UnixTimestamp::from_system_time())
+    }
+
+    /// Request the last `n` candles up to `end_time`.
+    pub fn last_n_until(
+        market: &'a str,
+        resolution: WindowLength,
+        n: u32,
+        end_time: UnixTimestamp,
+    ) -> Self {
+        let span_ms = u128::from(resolution.to_secs()) * u128::from(n) * 1000;
+
+        Self {
+            market,
+            resolution,
+            start_time: Some(UnixTimestamp::new(end_time.get().saturating_sub(span_ms))),
+            end_time: Some(end_time),
+        }
+    }
+
+    /// Check the request before sending it. The resolution is always
+    /// one FTX supports, see [`WindowLength::from_secs`], so this checks
+    /// `start_time` isn't after `end_time`, which FTX rejects.
+    pub fn validate(&self) -> Result<(), CandleRangeError> {
+        match (self.start_time, self.end_time) {
+            (Some(start_time), Some(end_time)) if start_time.get() > end_time.get() => {
+                Err(CandleRangeError {
+                    start_time,
+                    end_time,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CandleRangeError {
+    pub start_time: UnixTimestamp,
+    pub end_time: UnixTimestamp,
+}
+
+impl fmt::Display for CandleRangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "candle start time {} is after end time {}",
+            self.start_time.get(),
+            self.end_time.get()
+        )
+    }
+}
+
+impl StdError for CandleRangeError {}
+
 impl<'a> Sealed for GetCandles<'a> {}
 
 impl<'a> Request<false> for GetCandles<'a> {
@@ -217,6 +342,17 @@ impl<'a> Request<false> for GetCandles<'a> {
 
         Some(params)
     }
+
+    fn write_query(&self, query: &mut QueryWriter<'_>) {
+        query.append_uint("resolution", self.resolution.to_secs());
+
+        if let Some(start_time) = self.start_time {
+            query.append_uint("start_time", start_time.get());
+        }
+        if let Some(end_time) = self.end_time {
+            query.append_uint("end_time", end_time.get());
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -257,6 +393,170 @@ pub struct Market<'a> {
     pub is_etf_market: bool,
 }
 
+impl<'a> Market<'a> {
+    /// The smallest size of an order which rests on the book. Smaller
+    /// orders are rejected by the exchange, see
+    /// [`RejectionReason::SizeTooSmall`](crate::error::RejectionReason::SizeTooSmall).
+    pub fn min_size(&self) -> Decimal {
+        self.min_provide_size
+    }
+
+    /// Check that `size` is a multiple of this market's size increment, as
+    /// orders which aren't will be rejected by the exchange.
+    pub fn validate_size(&self, size: PositiveDecimal) -> Result<OrderSize, TryFromDecimalError> {
+        OrderSize::new(size, self.size_increment)
+    }
+
+    /// The number of price increments from `a` to `b`, negative if `b` is
+    /// below `a`. Partial increments are truncated towards zero.
+    pub fn ticks_between(&self, a: Decimal, b: Decimal) -> Result<i64, TickCountError> {
+        b.checked_sub(a)
+            .and_then(|diff| diff.checked_div(self.price_increment))
+            .and_then(|ticks| ticks.trunc().to_i64())
+            .ok_or(TickCountError {
+                a,
+                b,
+                price_increment: self.price_increment,
+            })
+    }
+
+    /// Check `order` against this market's trading rules, returning the
+    /// first rule it breaks. Orders which pass may still be rejected by
+    /// the exchange, e.g. for insufficient margin.
+    pub fn validate_order(&self, order: &PlaceOrder<'_>) -> Result<(), OrderValidationError> {
+        use OrderValidationError::*;
+
+        let opts = order.opts.unwrap_or_default();
+
+        if order.market != self.name {
+            return Err(WrongMarket);
+        }
+
+        if !self.enabled {
+            return Err(MarketDisabled);
+        }
+
+        if self.restricted {
+            return Err(MarketRestricted);
+        }
+
+        if self.post_only && opts.post_only != Some(true) {
+            return Err(PostOnlyRequired);
+        }
+
+        let size = order.size.get();
+
+        if self.validate_size(order.size).is_err() {
+            return Err(InvalidSize {
+                size,
+                size_increment: self.size_increment,
+            });
+        }
+
+        if let Some(price) = order.price.map(|p| p.get()) {
+            if self.price_increment <= Decimal::ZERO || !(price % self.price_increment).is_zero() {
+                return Err(InvalidPrice {
+                    price,
+                    price_increment: self.price_increment,
+                });
+            }
+
+            // Limit orders which may rest on the book must meet the
+            // minimum size for providing liquidity.
+            if opts.ioc != Some(true) && size < self.min_provide_size {
+                return Err(BelowMinProvideSize {
+                    size,
+                    min_provide_size: self.min_provide_size,
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct TickCountError {
+    a: Decimal,
+    b: Decimal,
+    price_increment: Decimal,
+}
+
+impl fmt::Display for TickCountError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "failed to count ticks of {} between {} and {}",
+            self.price_increment, self.a, self.b
+        )
+    }
+}
+
+impl StdError for TickCountError {}
+
+/// A market trading rule broken by an order, see
+/// [`Market::validate_order`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum OrderValidationError {
+    /// The order is for a different market.
+    WrongMarket,
+    MarketDisabled,
+    MarketRestricted,
+    /// The market only accepts post only orders.
+    PostOnlyRequired,
+    /// The size isn't a multiple of the market's size increment.
+    InvalidSize {
+        size: Decimal,
+        size_increment: Decimal,
+    },
+    /// The price isn't a multiple of the market's price increment.
+    InvalidPrice {
+        price: Decimal,
+        price_increment: Decimal,
+    },
+    /// A limit order is smaller than the market's minimum provide size.
+    BelowMinProvideSize {
+        size: Decimal,
+        min_provide_size: Decimal,
+    },
+}
+
+impl fmt::Display for OrderValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use OrderValidationError::*;
+
+        match self {
+            WrongMarket => f.write_str("order is for a different market"),
+            MarketDisabled => f.write_str("market is disabled"),
+            MarketRestricted => f.write_str("market is restricted"),
+            PostOnlyRequired => f.write_str("market only accepts post only orders"),
+            InvalidSize {
+                size,
+                size_increment,
+            } => write!(f, "size {} is not a multiple of {}", size, size_increment),
+            InvalidPrice {
+                price,
+                price_increment,
+            } => write!(
+                f,
+                "price {} is not a multiple of {}",
+                price, price_increment
+            ),
+            BelowMinProvideSize {
+                size,
+                min_provide_size,
+            } => write!(
+                f,
+                "size {} is below the minimum provide size {}",
+                size, min_provide_size
+            ),
+        }
+    }
+}
+
+impl StdError for OrderValidationError {}
+
 impl<'a> TryFrom<MarketPartial<'a>> for Market<'a> {
     type Error = serde_json::Error;
 
@@ -349,14 +649,329 @@ pub struct MarketPartial<'a> {
     pub is_etf_market: Json<'a, bool>,
 }
 
+impl<'a> MarketPartial<'a> {
+    /// As [`Market::min_size`], deserializing only that field.
+    pub fn min_size(&self) -> Result<Decimal, serde_json::Error> {
+        self.min_provide_size.deserialize()
+    }
+}
+
+/// Keep only ETF (leveraged token) markets. Only `is_etf_market` is
+/// deserialized.
+pub fn filter_etf_markets(
+    markets: Vec<MarketPartial<'_>>,
+) -> Result<Vec<MarketPartial<'_>>, serde_json::Error> {
+    filter_by_etf(markets, true)
+}
+
+/// Drop ETF (leveraged token) markets. Only `is_etf_market` is
+/// deserialized.
+pub fn filter_non_etf_markets(
+    markets: Vec<MarketPartial<'_>>,
+) -> Result<Vec<MarketPartial<'_>>, serde_json::Error> {
+    filter_by_etf(markets, false)
+}
+
+fn filter_by_etf(
+    markets: Vec<MarketPartial<'_>>,
+    is_etf: bool,
+) -> Result<Vec<MarketPartial<'_>>, serde_json::Error> {
+    let mut filtered = Vec::with_capacity(markets.len());
+
+    for market in markets {
+        if market.is_etf_market.deserialize()? == is_etf {
+            filtered.push(market);
+        }
+    }
+
+    Ok(filtered)
+}
+
+/// Split markets into `(spot, futures)`, keeping their order.
+pub fn partition_markets(markets: Vec<Market<'_>>) -> (Vec<Market<'_>>, Vec<Market<'_>>) {
+    markets
+        .into_iter()
+        .partition(|m| m.r#type == MarketType::Spot)
+}
+
+/// The `n` markets with the highest 24h USD volume, highest first.
+/// Markets without a volume are skipped.
+pub fn top_markets_by_volume<'a, 'b>(markets: &'b [Market<'a>], n: usize) -> Vec<&'b Market<'a>> {
+    let mut markets: Vec<_> = markets
+        .iter()
+        .filter(|m| m.volume_usd_24h.is_some())
+        .collect();
+
+    markets.sort_by_key(|m| Reverse(m.volume_usd_24h));
+    markets.truncate(n);
+    markets
+}
+
+/// Names in `expected` which don't match any of `markets`, in the order
+/// given. Useful to spot markets which have been delisted.
+pub fn missing_markets<'e>(markets: &[Market<'_>], expected: &[&'e str]) -> Vec<&'e str> {
+    let names: HashSet<&str> = markets.iter().map(|m| m.name).collect();
+
+    expected
+        .iter()
+        .copied()
+        .filter(|name| !names.contains(name))
+        .collect()
+}
+
+/// The window over which a market's price change is measured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChangeWindow {
+    OneHour,
+    OneDay,
+    /// Since the start of the current day.
+    BeginningOfDay,
+}
+
+impl ChangeWindow {
+    fn change(&self, market: &Market<'_>) -> Option<Decimal> {
+        match self {
+            Self::OneHour => market.change_1h,
+            Self::OneDay => market.change_24h,
+            Self::BeginningOfDay => market.change_bod,
+        }
+    }
+}
+
+/// The `n` markets with the largest price increase over `window`,
+/// largest first. Markets without a change are skipped.
+pub fn top_movers<'a, 'b>(
+    markets: &'b [Market<'a>],
+    window: ChangeWindow,
+    n: usize,
+) -> Vec<&'b Market<'a>> {
+    let mut markets = with_change(markets, window);
+    markets.sort_by_key(|m| Reverse(window.change(m)));
+    markets.truncate(n);
+    markets
+}
+
+/// The `n` markets with the largest price decrease over `window`,
+/// largest first. Markets without a change are skipped.
+pub fn bottom_movers<'a, 'b>(
+    markets: &'b [Market<'a>],
+    window: ChangeWindow,
+    n: usize,
+) -> Vec<&'b Market<'a>> {
+    let mut markets = with_change(markets, window);
+    markets.sort_by_key(|m| window.change(m));
+    markets.truncate(n);
+    markets
+}
+
+fn with_change<'a, 'b>(markets: &'b [Market<'a>], window: ChangeWindow) -> Vec<&'b Market<'a>> {
+    markets
+        .iter()
+        .filter(|m| window.change(m).is_some())
+        .collect()
+}
+
+/// Whether consecutive candles start exactly `resolution` apart. The
+/// exchange may return coarser candles than requested rather than
+/// failing, this catches that.
+pub fn candles_match_resolution(candles: &[Candle], resolution: WindowLength) -> bool {
+    let expected = Duration::seconds(resolution.to_secs() as i64);
+
+    candles
+        .windows(2)
+        .all(|w| w[1].start_time.get() - w[0].start_time.get() == expected)
+}
+
+/// Merge candles of length `from` into longer candles of length `to`,
+/// which must be a multiple of `from`. Candles are grouped into buckets
+/// aligned to multiples of `to` since the UNIX epoch and should be
+/// sorted by start time.
+pub fn resample_candles(
+    candles: &[Candle],
+    from: WindowLength,
+    to: WindowLength,
+) -> Result<Vec<Candle>, ResampleError> {
+    if to.to_secs() % from.to_secs() != 0 {
+        return Err(ResampleError { from, to });
+    }
+
+    let bucket_secs = to.to_secs() as i64;
+
+    let mut resampled: Vec<Candle> = Vec::new();
+
+    for candle in candles {
+        let ts = candle.start_time.get().unix_timestamp();
+        let bucket = ts - ts.rem_euclid(bucket_secs);
+
+        match resampled.last_mut() {
+            Some(last) if last.start_time.get().unix_timestamp() == bucket => {
+                last.high = last.high.max(candle.high);
+                last.low = last.low.min(candle.low);
+                last.close = candle.close;
+                last.volume += candle.volume;
+            }
+            _ => {
+                let start_time = OffsetDateTime::from_unix_timestamp(bucket)
+                    .expect("bucket is no earlier than a valid candle");
+
+                resampled.push(Candle {
+                    start_time: start_time.into(),
+                    time: (bucket * 1000) as f64,
+                    ..*candle
+                });
+            }
+        }
+    }
+
+    Ok(resampled)
+}
+
+#[derive(Debug)]
+pub struct ResampleError {
+    from: WindowLength,
+    to: WindowLength,
+}
+
+impl fmt::Display for ResampleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "cannot resample {}s candles into {}s candles",
+            self.from.to_secs(),
+            self.to.to_secs()
+        )
+    }
+}
+
+impl StdError for ResampleError {}
+
+/// An owned order book, as returned by [`GetOrderBookResponse`]'s
+/// `deserialize`. Levels are `(price, size)` pairs ordered best first.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "deny-unknown-fields", serde(deny_unknown_fields))]
 pub struct OrderBook {
     pub asks: Vec<(Price, Size)>,
     pub bids: Vec<(Price, Size)>,
+    /// When the snapshot was taken, only included in some responses.
+    pub time: Option<FtxDateTime>,
 }
 
+impl OrderBook {
+    /// The highest bid. Assumes levels are ordered best first, as
+    /// returned by the exchange.
+    pub fn best_bid(&self) -> Option<(Price, Size)> {
+        self.bids.first().copied()
+    }
+
+    /// The lowest ask. Assumes levels are ordered best first, as
+    /// returned by the exchange.
+    pub fn best_ask(&self) -> Option<(Price, Size)> {
+        self.asks.first().copied()
+    }
+
+    /// The spread between the best bid and ask in basis points of the mid
+    /// price. `None` if either side of the book is empty.
+    pub fn spread_bps(&self) -> Option<Decimal> {
+        let (bid, _) = self.best_bid()?;
+        let (ask, _) = self.best_ask()?;
+
+        let mid = bid.checked_add(ask)? / Decimal::TWO;
+
+        (ask - bid)
+            .checked_div(mid)?
+            .checked_mul(Decimal::new(10_000, 0))
+    }
+
+    /// The spread between the best bid and ask in price increments,
+    /// truncating partial increments. `None` if either side of the book
+    /// is empty.
+    pub fn spread_ticks(&self, price_increment: Decimal) -> Option<i64> {
+        let (bid, _) = self.best_bid()?;
+        let (ask, _) = self.best_ask()?;
+
+        (ask - bid).checked_div(price_increment)?.trunc().to_i64()
+    }
+
+    /// Estimate the result of a market order for `size` by walking the
+    /// asks for a buy or the bids for a sell. Assumes levels are ordered
+    /// best first, as returned by the exchange.
+    pub fn estimate_fill(
+        &self,
+        side: Side,
+        size: Decimal,
+    ) -> Result<FillEstimate, FillEstimateError> {
+        if size <= Decimal::ZERO {
+            return Err(FillEstimateError::InvalidSize(size));
+        }
+
+        let levels = match side {
+            Side::Buy => &self.asks,
+            Side::Sell => &self.bids,
+        };
+
+        let mut filled_size = Decimal::ZERO;
+        let mut cost = Decimal::ZERO;
+
+        for &(price, level_size) in levels {
+            let take = level_size.min(size - filled_size);
+
+            cost = take
+                .checked_mul(price)
+                .and_then(|c| c.checked_add(cost))
+                .ok_or(FillEstimateError::Overflow)?;
+            filled_size += take;
+
+            if filled_size == size {
+                break;
+            }
+        }
+
+        if filled_size.is_zero() {
+            return Err(FillEstimateError::EmptyBook);
+        }
+
+        Ok(FillEstimate {
+            avg_price: cost / filled_size,
+            filled_size,
+            unfilled_size: size - filled_size,
+        })
+    }
+}
+
+/// The estimated result of a market order, see
+/// [`OrderBook::estimate_fill`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FillEstimate {
+    pub avg_price: Decimal,
+    pub filled_size: Decimal,
+    /// Non-zero if the book is too thin to fill the whole order.
+    pub unfilled_size: Decimal,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FillEstimateError {
+    /// The order size wasn't positive.
+    InvalidSize(Decimal),
+    /// There are no levels on the side of the book the order would
+    /// fill against.
+    EmptyBook,
+    Overflow,
+}
+
+impl fmt::Display for FillEstimateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidSize(size) => write!(f, "invalid order size {}", size),
+            Self::EmptyBook => f.write_str("no levels to fill against"),
+            Self::Overflow => f.write_str("overflow while estimating fill"),
+        }
+    }
+}
+
+impl StdError for FillEstimateError {}
+
 impl<'a> TryFrom<OrderBookPartial<'a>> for OrderBook {
     type Error = serde_json::Error;
 
@@ -371,7 +986,11 @@ impl<'a> TryFrom<OrderBookPartial<'a>> for OrderBook {
             bids.push((p.deserialize()?, s.deserialize()?));
         }
 
-        Ok(Self { asks, bids })
+        Ok(Self {
+            asks,
+            bids,
+            time: val.time.deserialize()?,
+        })
     }
 }
 
@@ -383,6 +1002,8 @@ pub struct OrderBookPartial<'a> {
     pub asks: Vec<(Json<'a, Price>, Json<'a, Size>)>,
     #[serde(borrow)]
     pub bids: Vec<(Json<'a, Price>, Json<'a, Size>)>,
+    #[serde(borrow, default)]
+    pub time: OptJson<'a, FtxDateTime>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -483,10 +1104,25 @@ pub struct CandlePartial<'a> {
 mod tests {
     use std::convert::TryInto;
 
-    use crate::Response;
-
     use super::*;
 
+    #[test]
+    fn book_depth() {
+        let depth = |d: u8| NonZeroU8::new(d).unwrap();
+
+        assert_eq!(BookDepth::try_new(depth(1)), Ok(BookDepth(1)));
+        assert_eq!(BookDepth::try_new(depth(100)), Ok(BookDepth(100)));
+        assert_eq!(BookDepth::new(depth(100)), Some(BookDepth(100)));
+
+        let err = BookDepth::try_new(depth(101)).unwrap_err();
+        assert_eq!(err, BookDepthError { depth: 101 });
+        assert_eq!(
+            err.to_string(),
+            "book depth of 101 is greater than the maximum of 100"
+        );
+        assert_eq!(BookDepth::new(depth(255)), None);
+    }
+
     #[test]
     fn get_markets() {
         let json = r#"
@@ -534,8 +1170,78 @@ mod tests {
     }
 
     #[test]
-    fn get_market() {
+    fn filter_etf() {
         let json = r#"
+{
+  "success": true,
+  "result": [
+    {
+      "name": "BTC-PERP",
+      "baseCurrency": null,
+      "quoteCurrency": null,
+      "quoteVolume24h": 28914.76,
+      "change1h": 0.012,
+      "change24h": 0.0299,
+      "changeBod": 0.0156,
+      "highLeverageFeeExempt": false,
+      "minProvideSize": 0.001,
+      "type": "future",
+      "underlying": "BTC",
+      "enabled": true,
+      "ask": 3949.25,
+      "bid": 3949,
+      "last": 10579.52,
+      "postOnly": false,
+      "price": 10579.52,
+      "priceIncrement": 0.25,
+      "sizeIncrement": 0.0001,
+      "restricted": false,
+      "volumeUsd24h": 28914.76,
+      "largeOrderThreshold": 5000.0,
+      "isEtfMarket": false
+    },
+    {
+      "name": "BULL/USD",
+      "baseCurrency": "BULL",
+      "quoteCurrency": "USD",
+      "quoteVolume24h": 1208.41,
+      "change1h": 0.021,
+      "change24h": 0.0581,
+      "changeBod": 0.0301,
+      "highLeverageFeeExempt": true,
+      "minProvideSize": 0.00001,
+      "type": "spot",
+      "underlying": null,
+      "enabled": true,
+      "ask": 12.5,
+      "bid": 12.4,
+      "last": 12.45,
+      "postOnly": false,
+      "price": 12.45,
+      "priceIncrement": 0.05,
+      "sizeIncrement": 0.00001,
+      "restricted": false,
+      "volumeUsd24h": 1208.41,
+      "largeOrderThreshold": 1000.0,
+      "isEtfMarket": true
+    }
+  ]
+}
+"#;
+        let response = GetMarketsResponse(json.as_bytes().into());
+
+        let etf = filter_etf_markets(response.deserialize_partial().unwrap()).unwrap();
+
+        assert_eq!(etf.len(), 1);
+        assert_eq!(etf[0].name, "BULL/USD");
+
+        let non_etf = filter_non_etf_markets(response.deserialize_partial().unwrap()).unwrap();
+
+        assert_eq!(non_etf.len(), 1);
+        assert_eq!(non_etf[0].name, "BTC-PERP");
+    }
+
+    const GET_MARKET_JSON: &str = r#"
 {
   "success": true,
   "result": {
@@ -565,11 +1271,422 @@ mod tests {
     }
 }
 "#;
+
+    #[test]
+    fn get_market() {
+        let json = GET_MARKET_JSON;
         let response = GetMarketResponse(json.as_bytes().into());
 
         let from_partial: Market<'_> = response.deserialize_partial().unwrap().try_into().unwrap();
 
         assert_eq!(response.deserialize().unwrap(), from_partial);
+
+        let size = PositiveDecimal::new(Decimal::new(12345, 4)).unwrap();
+        assert_eq!(from_partial.validate_size(size).unwrap().get(), size);
+
+        let size = PositiveDecimal::new(Decimal::new(12345, 5)).unwrap();
+        assert!(from_partial.validate_size(size).is_err());
+    }
+
+    #[test]
+    fn min_size() {
+        let response = GetMarketResponse(GET_MARKET_JSON.as_bytes().into());
+
+        assert_eq!(
+            response.deserialize().unwrap().min_size(),
+            Decimal::new(1, 3)
+        );
+        assert_eq!(
+            response.deserialize_partial().unwrap().min_size().unwrap(),
+            Decimal::new(1, 3)
+        );
+    }
+
+    #[test]
+    fn validate_order() {
+        use crate::endpoints::orders::OrderOpts;
+        use OrderValidationError::*;
+
+        let response = GetMarketResponse(GET_MARKET_JSON.as_bytes().into());
+        let market = response.deserialize().unwrap();
+
+        let positive = |d| PositiveDecimal::new(d).unwrap();
+
+        let order = PlaceOrder {
+            market: "BTC-PERP",
+            side: Side::Buy,
+            price: Some(positive(Decimal::new(394925, 2))),
+            size: positive(Decimal::new(12, 3)),
+            client_id: None,
+            opts: None,
+        };
+
+        assert_eq!(market.validate_order(&order), Ok(()));
+        assert_eq!(
+            market.validate_order(&PlaceOrder {
+                price: None,
+                ..order
+            }),
+            Ok(())
+        );
+
+        assert_eq!(
+            market.validate_order(&PlaceOrder {
+                market: "ETH-PERP",
+                ..order
+            }),
+            Err(WrongMarket)
+        );
+        assert_eq!(
+            Market {
+                enabled: false,
+                ..market
+            }
+            .validate_order(&order),
+            Err(MarketDisabled)
+        );
+        assert_eq!(
+            Market {
+                restricted: true,
+                ..market
+            }
+            .validate_order(&order),
+            Err(MarketRestricted)
+        );
+
+        let post_only_market = Market {
+            post_only: true,
+            ..market
+        };
+        assert_eq!(
+            post_only_market.validate_order(&order),
+            Err(PostOnlyRequired)
+        );
+        assert_eq!(
+            post_only_market.validate_order(&PlaceOrder {
+                opts: Some(OrderOpts {
+                    post_only: Some(true),
+                    ..OrderOpts::default()
+                }),
+                ..order
+            }),
+            Ok(())
+        );
+
+        assert_eq!(
+            market.validate_order(&PlaceOrder {
+                size: positive(Decimal::new(12345, 5)),
+                ..order
+            }),
+            Err(InvalidSize {
+                size: Decimal::new(12345, 5),
+                size_increment: Decimal::new(1, 4),
+            })
+        );
+        assert_eq!(
+            market.validate_order(&PlaceOrder {
+                price: Some(positive(Decimal::new(39491, 1))),
+                ..order
+            }),
+            Err(InvalidPrice {
+                price: Decimal::new(39491, 1),
+                price_increment: Decimal::new(25, 2),
+            })
+        );
+
+        let small_order = PlaceOrder {
+            size: positive(Decimal::new(5, 4)),
+            ..order
+        };
+        assert_eq!(
+            market.validate_order(&small_order),
+            Err(BelowMinProvideSize {
+                size: Decimal::new(5, 4),
+                min_provide_size: Decimal::new(1, 3),
+            })
+        );
+        assert_eq!(
+            market.validate_order(&PlaceOrder {
+                opts: Some(OrderOpts {
+                    ioc: Some(true),
+                    ..OrderOpts::default()
+                }),
+                ..small_order
+            }),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn top_by_volume() {
+        let response = GetMarketResponse(GET_MARKET_JSON.as_bytes().into());
+        let market = response.deserialize().unwrap();
+
+        let markets = [
+            Market {
+                name: "BTC-PERP",
+                volume_usd_24h: Some(Decimal::new(100, 0)),
+                ..market
+            },
+            Market {
+                name: "ETH-PERP",
+                volume_usd_24h: None,
+                ..market
+            },
+            Market {
+                name: "SOL-PERP",
+                volume_usd_24h: Some(Decimal::new(300, 0)),
+                ..market
+            },
+            Market {
+                name: "XRP-PERP",
+                volume_usd_24h: Some(Decimal::new(200, 0)),
+                ..market
+            },
+        ];
+
+        let top: Vec<_> = top_markets_by_volume(&markets, 2)
+            .into_iter()
+            .map(|m| m.name)
+            .collect();
+
+        assert_eq!(top, ["SOL-PERP", "XRP-PERP"]);
+        assert_eq!(top_markets_by_volume(&markets, 10).len(), 3);
+    }
+
+    #[test]
+    fn partition() {
+        let response = GetMarketResponse(GET_MARKET_JSON.as_bytes().into());
+        let market = response.deserialize().unwrap();
+
+        let spot = |name| Market {
+            name,
+            r#type: MarketType::Spot,
+            ..market
+        };
+
+        let (spot, futures) = partition_markets(vec![
+            spot("BTC/USD"),
+            market,
+            spot("ETH/USD"),
+            Market {
+                name: "ETH-PERP",
+                ..market
+            },
+        ]);
+
+        fn names<'a>(markets: Vec<Market<'a>>) -> Vec<&'a str> {
+            markets.into_iter().map(|m| m.name).collect()
+        }
+
+        assert_eq!(names(spot), ["BTC/USD", "ETH/USD"]);
+        assert_eq!(names(futures), ["BTC-PERP", "ETH-PERP"]);
+    }
+
+    #[test]
+    fn missing() {
+        let response = GetMarketResponse(GET_MARKET_JSON.as_bytes().into());
+        let market = response.deserialize().unwrap();
+
+        let markets = [
+            market,
+            Market {
+                name: "ETH-PERP",
+                ..market
+            },
+        ];
+
+        assert_eq!(
+            missing_markets(&markets, &["ETH-PERP", "LUNA-PERP", "BTC-PERP"]),
+            ["LUNA-PERP"]
+        );
+        assert!(missing_markets(&markets, &["BTC-PERP"]).is_empty());
+    }
+
+    #[test]
+    fn movers() {
+        let response = GetMarketResponse(GET_MARKET_JSON.as_bytes().into());
+        let market = response.deserialize().unwrap();
+
+        let markets = [
+            Market {
+                name: "BTC-PERP",
+                change_24h: Some(Decimal::new(2, 2)),
+                ..market
+            },
+            Market {
+                name: "ETH-PERP",
+                change_24h: None,
+                ..market
+            },
+            Market {
+                name: "SOL-PERP",
+                change_24h: Some(Decimal::new(-5, 2)),
+                ..market
+            },
+            Market {
+                name: "XRP-PERP",
+                change_24h: Some(Decimal::new(7, 2)),
+                ..market
+            },
+        ];
+
+        fn names<'a>(markets: Vec<&Market<'a>>) -> Vec<&'a str> {
+            markets.into_iter().map(|m| m.name).collect()
+        }
+
+        assert_eq!(
+            names(top_movers(&markets, ChangeWindow::OneDay, 2)),
+            ["XRP-PERP", "BTC-PERP"]
+        );
+        assert_eq!(
+            names(bottom_movers(&markets, ChangeWindow::OneDay, 2)),
+            ["SOL-PERP", "BTC-PERP"]
+        );
+        assert_eq!(top_movers(&markets, ChangeWindow::OneDay, 10).len(), 3);
+    }
+
+    #[test]
+    fn ticks_between() {
+        let response = GetMarketResponse(GET_MARKET_JSON.as_bytes().into());
+        let mut market = response.deserialize().unwrap();
+
+        let (a, b) = (Decimal::new(10000, 2), Decimal::new(10100, 2));
+
+        assert_eq!(market.ticks_between(a, b).unwrap(), 4);
+        assert_eq!(market.ticks_between(b, a).unwrap(), -4);
+        assert_eq!(market.ticks_between(a, a).unwrap(), 0);
+
+        market.price_increment = Decimal::ZERO;
+        assert!(market.ticks_between(a, b).is_err());
+    }
+
+    #[test]
+    fn get_orderbook() {
+        let json = r#"
+{
+  "success": true,
+  "result": {
+    "asks": [
+      [4114.25, 6.263]
+    ],
+    "bids": [
+      [4112.25, 49.29],
+      [4112.0, 3.5]
+    ]
+  }
+}
+"#;
+        let response = GetOrderBookResponse(json.as_bytes().into());
+
+        let from_partial: OrderBook = response.deserialize_partial().unwrap().try_into().unwrap();
+
+        assert_eq!(response.deserialize().unwrap(), from_partial);
+        assert_eq!(response.level_count().unwrap(), (2, 1));
+        assert_eq!(from_partial.time, None);
+    }
+
+    #[test]
+    fn get_orderbook_with_time() {
+        let json = r#"
+{
+  "success": true,
+  "result": {
+    "asks": [
+      [4114.25, 6.263]
+    ],
+    "bids": [
+      [4112.25, 49.29]
+    ],
+    "time": "2019-03-20T17:43:20.416105+00:00"
+  }
+}
+"#;
+        let response = GetOrderBookResponse(json.as_bytes().into());
+
+        let from_partial: OrderBook = response.deserialize_partial().unwrap().try_into().unwrap();
+
+        assert_eq!(response.deserialize().unwrap(), from_partial);
+        assert_eq!(
+            from_partial.time.unwrap().get(),
+            time::macros::datetime!(2019-03-20 17:43:20.416105 UTC)
+        );
+    }
+
+    #[test]
+    fn spread() {
+        let book = OrderBook {
+            asks: vec![
+                (Decimal::new(10010, 2), Decimal::ONE),
+                (Decimal::new(10020, 2), Decimal::ONE),
+            ],
+            bids: vec![(Decimal::new(9990, 2), Decimal::ONE)],
+            time: None,
+        };
+
+        assert_eq!(book.best_bid(), Some((Decimal::new(9990, 2), Decimal::ONE)));
+        assert_eq!(
+            book.best_ask(),
+            Some((Decimal::new(10010, 2), Decimal::ONE))
+        );
+        assert_eq!(book.spread_bps(), Some(Decimal::new(20, 0)));
+        assert_eq!(book.spread_ticks(Decimal::new(5, 2)), Some(4));
+        assert_eq!(book.spread_ticks(Decimal::new(3, 2)), Some(6));
+        assert_eq!(book.spread_ticks(Decimal::ZERO), None);
+
+        let one_sided = OrderBook {
+            bids: vec![],
+            ..book
+        };
+
+        assert_eq!(one_sided.spread_bps(), None);
+        assert_eq!(one_sided.spread_ticks(Decimal::new(5, 2)), None);
+    }
+
+    #[test]
+    fn estimate_fill() {
+        let book = OrderBook {
+            asks: vec![
+                (Decimal::new(100, 0), Decimal::new(1, 0)),
+                (Decimal::new(101, 0), Decimal::new(2, 0)),
+            ],
+            bids: vec![
+                (Decimal::new(99, 0), Decimal::new(1, 0)),
+                (Decimal::new(98, 0), Decimal::new(1, 0)),
+            ],
+            time: None,
+        };
+
+        assert_eq!(
+            book.estimate_fill(Side::Buy, Decimal::new(2, 0)).unwrap(),
+            FillEstimate {
+                avg_price: Decimal::new(1005, 1),
+                filled_size: Decimal::new(2, 0),
+                unfilled_size: Decimal::ZERO,
+            }
+        );
+        assert_eq!(
+            book.estimate_fill(Side::Sell, Decimal::new(3, 0)).unwrap(),
+            FillEstimate {
+                avg_price: Decimal::new(985, 1),
+                filled_size: Decimal::new(2, 0),
+                unfilled_size: Decimal::new(1, 0),
+            }
+        );
+
+        assert_eq!(
+            book.estimate_fill(Side::Buy, Decimal::ZERO),
+            Err(FillEstimateError::InvalidSize(Decimal::ZERO))
+        );
+        assert_eq!(
+            OrderBook {
+                asks: vec![],
+                bids: vec![],
+                time: None,
+            }
+            .estimate_fill(Side::Buy, Decimal::ONE),
+            Err(FillEstimateError::EmptyBook)
+        );
     }
 
     #[test]
@@ -630,4 +1747,174 @@ mod tests {
 
         assert_eq!(response.deserialize().unwrap(), from_partial);
     }
+
+    #[test]
+    fn trades_for_day() {
+        let request = GetTrades::for_day("BTC-PERP", time::macros::date!(2022 - 04 - 03)).unwrap();
+        let (start, end) = (request.start_time.unwrap(), request.end_time.unwrap());
+
+        assert_eq!(start, UnixTimestamp::new(1648944000000));
+        assert_eq!(end.get() - start.get(), 86_400 * 1000);
+
+        assert!(GetTrades::for_day("BTC-PERP", time::macros::date!(1969 - 12 - 31)).is_err());
+    }
+
+    #[test]
+    fn last_n_candles() {
+        let end_time = UnixTimestamp::new(1648996980000);
+
+        let request = GetCandles::last_n_until("BTC-PERP", WindowLength::OneMinute, 100, end_time);
+
+        assert_eq!(request.end_time, Some(end_time));
+        assert_eq!(
+            request.start_time,
+            Some(UnixTimestamp::new(1648996980000 - 6000 * 1000))
+        );
+
+        let request = GetCandles::last_n("BTC-PERP", WindowLength::OneMinute, 100);
+        let (start, end) = (request.start_time.unwrap(), request.end_time.unwrap());
+
+        assert_eq!(end.get() - start.get(), 6000 * 1000);
+    }
+
+    #[test]
+    fn validate_candles() {
+        let end_time = UnixTimestamp::new(1648996980000);
+        let request = GetCandles::last_n_until("BTC-PERP", WindowLength::OneMinute, 100, end_time);
+
+        assert_eq!(request.validate(), Ok(()));
+        assert_eq!(
+            GetCandles {
+                start_time: None,
+                ..request
+            }
+            .validate(),
+            Ok(())
+        );
+
+        let inverted = GetCandles {
+            start_time: request.end_time,
+            end_time: request.start_time,
+            ..request
+        };
+        assert_eq!(
+            inverted.validate(),
+            Err(CandleRangeError {
+                start_time: end_time,
+                end_time: request.start_time.unwrap(),
+            })
+        );
+    }
+
+    const GET_CANDLES_JSON: &str = r#"
+{
+  "success": true,
+  "result": [
+    {
+      "startTime": "2022-04-03T14:43:00+00:00",
+      "time": 1648996980000.0,
+      "open": 46371,
+      "high": 46381,
+      "low": 46371,
+      "close": 46380,
+      "volume": 1051438.0941
+    },
+    {
+      "startTime": "2022-04-03T14:44:00+00:00",
+      "time": 1648997040000.0,
+      "open": 46380,
+      "high": 46392,
+      "low": 46377,
+      "close": 46390,
+      "volume": 873204.5521
+    }
+  ]
+}
+"#;
+
+    #[test]
+    fn candle_resolution() {
+        let response = GetCandlesResponse(GET_CANDLES_JSON.as_bytes().into());
+        let candles = response.deserialize().unwrap();
+
+        assert!(candles_match_resolution(&candles, WindowLength::OneMinute));
+        assert!(!candles_match_resolution(
+            &candles,
+            WindowLength::FiveMinutes
+        ));
+        assert!(candles_match_resolution(
+            &candles[..1],
+            WindowLength::FiveMinutes
+        ));
+    }
+
+    #[test]
+    fn resample() {
+        use time::macros::datetime;
+
+        let candle = |start_time: OffsetDateTime, open: i64, close: i64| Candle {
+            close: Decimal::new(close, 0),
+            high: Decimal::new(open.max(close) + 1, 0),
+            low: Decimal::new(open.min(close) - 1, 0),
+            open: Decimal::new(open, 0),
+            volume: Decimal::new(10, 0),
+            start_time: start_time.into(),
+            time: (start_time.unix_timestamp() * 1000) as f64,
+        };
+
+        let candles = [
+            candle(datetime!(2022-04-03 14:43:00 UTC), 100, 104),
+            candle(datetime!(2022-04-03 14:43:15 UTC), 104, 98),
+            candle(datetime!(2022-04-03 14:43:30 UTC), 98, 110),
+            candle(datetime!(2022-04-03 14:43:45 UTC), 110, 107),
+            candle(datetime!(2022-04-03 14:44:00 UTC), 107, 105),
+        ];
+
+        let resampled = resample_candles(
+            &candles,
+            WindowLength::FifteenSeconds,
+            WindowLength::OneMinute,
+        )
+        .unwrap();
+
+        assert_eq!(
+            resampled,
+            [
+                Candle {
+                    close: Decimal::new(107, 0),
+                    high: Decimal::new(111, 0),
+                    low: Decimal::new(97, 0),
+                    open: Decimal::new(100, 0),
+                    volume: Decimal::new(40, 0),
+                    ..candles[0]
+                },
+                candles[4]
+            ]
+        );
+        assert!(candles_match_resolution(
+            &resampled,
+            WindowLength::OneMinute
+        ));
+
+        assert!(
+            resample_candles(&candles, WindowLength::FiveMinutes, WindowLength::OneMinute).is_err()
+        );
+    }
+
+    #[test]
+    fn candles_to_ndjson() {
+        let json = GET_CANDLES_JSON;
+        let response = GetCandlesResponse(json.as_bytes().into());
+
+        let mut buf = Vec::new();
+        response.write_ndjson(&mut buf).unwrap();
+
+        let lines: Vec<Candle> = std::str::from_utf8(&buf)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+
+        assert_eq!(lines, response.deserialize().unwrap());
+    }
 }
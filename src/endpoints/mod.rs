@@ -5,9 +5,11 @@ pub mod futures;
 pub mod indices;
 pub mod markets;
 pub mod orders;
+pub mod raw;
 pub mod spot_margin;
 pub mod statistics;
 pub mod subaccounts;
+pub mod trigger_orders;
 pub mod wallet;
 
 use serde::{Deserialize, Deserializer, Serialize};
@@ -20,11 +22,61 @@ use crate::{
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct FtxResponse<'a, T> {
-    #[serde(borrow, deserialize_with = "deserialize_some")]
+    // Rejections carry only `success` and `error`, so a missing result is
+    // a rejection rather than a malformed response.
+    #[serde(
+        borrow,
+        default = "Option::default",
+        deserialize_with = "deserialize_some"
+    )]
     result: Option<Json<'a, T>>,
     error: Option<&'a str>,
 }
 
+impl<'a, T> FtxResponse<'a, T> {
+    pub(crate) fn error(&self) -> Option<&'a str> {
+        self.error
+    }
+
+    /// Whether the result is an empty array, checked on the raw JSON
+    /// without deserializing any elements.
+    pub(crate) fn is_empty_array(&self) -> Result<bool, Error> {
+        let res = self.result.as_ref().ok_or_else(|| self.rejected())?;
+
+        res.val
+            .get()
+            .trim_start()
+            .strip_prefix('[')
+            .map(|rest| rest.trim_start().starts_with(']'))
+            .ok_or_else(|| {
+                Error::new(ErrorKind::DeserializationFailed).with_source("result is not an array")
+            })
+    }
+
+    pub(crate) fn ensure_success(&self) -> Result<(), Error> {
+        match self.result {
+            Some(_) => Ok(()),
+            None => Err(self.rejected()),
+        }
+    }
+
+    fn rejected(&self) -> Error {
+        if let Some(err) = self.error {
+            // Sent when the exchange fails to process a request on its
+            // side, the same request is expected to succeed later.
+            let kind = if err.eq_ignore_ascii_case("Please retry request") {
+                ErrorKind::RetryRequested
+            } else {
+                ErrorKind::RejectedByExchange
+            };
+
+            Error::new(kind).with_exchange_message(err)
+        } else {
+            Error::new(ErrorKind::RejectedByExchange)
+        }
+    }
+}
+
 impl<'a, T> FtxResponse<'a, T>
 where
     T: Deserialize<'a>,
@@ -33,14 +85,18 @@ where
         if let Some(res) = self.result {
             res.deserialize()
                 .map_err(|e| Error::new(ErrorKind::DeserializationFailed).with_source(e))
-        } else if let Some(err) = self.error {
-            Err(Error::new(ErrorKind::RejectedByExchange).with_source(err))
         } else {
-            Err(Error::new(ErrorKind::RejectedByExchange))
+            Err(self.rejected())
         }
     }
 }
 
+// Parsing deliberately stays on `serde_json`. `Json` wraps a borrowed
+// `RawValue` so fields can be deserialized lazily straight out of the
+// response bytes. Parsers like `simd-json` need a mutable buffer to
+// unescape strings in place and have no `RawValue` equivalent, so they
+// can't be swapped in here without copying the body and giving up the
+// borrowed response types.
 impl<'a, T> TryFrom<&'a [u8]> for FtxResponse<'a, T>
 where
     T: Deserialize<'a>,
@@ -91,15 +147,289 @@ mod macros {
 
 #[cfg(test)]
 mod tests {
+    use bytes::Bytes;
+    use std::error::Error as StdError;
+
+    use rust_decimal::Decimal;
+
+    use crate::{
+        data::{PositiveDecimal, Side, SortOrder, UnixTimestamp, WindowLength},
+        error::{Error, ErrorKind},
+        QueryWriter, RateLimitClass, Request, Response,
+    };
+
+    use super::{
+        fills::GetFills,
+        futures::GetFundingRatesResponse,
+        markets::{GetCandles, GetMarketResponse, GetMarkets},
+        orders::{CancelOrder, GetOpenOrders, GetOrderHistory, OrderId, OrderType, PlaceOrder},
+        trigger_orders::{
+            CancelTriggerOrder, GetOpenTriggerOrders, GetTriggerOrderHistory, TriggerOrderType,
+        },
+    };
+
+    fn assert_query_matches_params<R, const AUTH: bool>(request: &R)
+    where
+        R: Request<AUTH>,
+    {
+        let expected = request
+            .query_params()
+            .map(|p| serde_urlencoded::to_string(p).unwrap())
+            .unwrap_or_default();
+
+        let mut query = String::new();
+        request.write_query(&mut QueryWriter::new(&mut query));
+
+        assert_eq!(query, expected);
+    }
+
     #[test]
-    fn url_encoding_works() {
-        let mut params: Vec<(&'static str, String)> = Vec::new();
+    fn error_message() {
+        let response = GetMarketResponse::from(Bytes::from_static(
+            br#"{"success": false, "error": "No such market: BTC-PERPS"}"#,
+        ));
+
+        assert_eq!(response.error_message(), Some("No such market: BTC-PERPS"));
+
+        let err = response.deserialize().unwrap_err();
+        assert_eq!(err.exchange_message(), Some("No such market: BTC-PERPS"));
+        assert_eq!(
+            err.source().unwrap().to_string(),
+            "No such market: BTC-PERPS"
+        );
+
+        let response = GetFundingRatesResponse::from(Bytes::from_static(
+            br#"{"success": true, "result": []}"#,
+        ));
+
+        assert_eq!(response.error_message(), None);
+    }
+
+    #[test]
+    fn into_result() {
+        let response = GetMarketResponse::from(Bytes::from_static(
+            br#"{"success": false, "error": "No such market: BTC-PERPS"}"#,
+        ));
+
+        let err = response.into_result().unwrap_err();
+        assert_eq!(
+            err.source().unwrap().to_string(),
+            "No such market: BTC-PERPS"
+        );
+
+        // The result isn't deserialized, so a malformed one is only caught
+        // later.
+        let response = GetMarketResponse::from(Bytes::from_static(
+            br#"{"success": true, "result": {"name": "BTC-PERP"}}"#,
+        ));
+
+        let response = response.into_result().unwrap();
+        assert!(response.deserialize().is_err());
+    }
+
+    #[test]
+    fn get_path() {
+        let response = GetMarketResponse::from(Bytes::from_static(
+            br#"{"success": true, "result": {"name": "BTC-PERP", "price": 39511.0, "a/b": [1, {"c": 2}]}}"#,
+        ));
+
+        let price: Decimal =
+            serde_json::from_str(response.get_path("/result/price").unwrap().unwrap().get())
+                .unwrap();
+        assert_eq!(price, Decimal::new(39511, 0));
+
+        assert_eq!(
+            response
+                .get_path("/result/a~1b/1/c")
+                .unwrap()
+                .unwrap()
+                .get(),
+            "2"
+        );
+        assert!(response.get_path("/result/a~1b/2").unwrap().is_none());
+        assert!(response.get_path("/result/a~1b/01").unwrap().is_none());
+        assert!(response.get_path("/result/volume").unwrap().is_none());
+        assert!(response.get_path("/success/x").unwrap().is_none());
+        assert!(response.get_path("result").unwrap().is_none());
+        assert!(response.get_path("").unwrap().is_some());
+
+        let response = GetMarketResponse::from(Bytes::from_static(b"{"));
+        assert!(response.get_path("/result").is_err());
+    }
+
+    #[test]
+    fn missing_result_is_rejection() {
+        let response = GetMarketResponse::from(Bytes::from_static(
+            br#"{"success": false, "error": "Not logged in"}"#,
+        ));
+
+        let err = response.deserialize().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::RejectedByExchange);
+        assert_eq!(err.exchange_message(), Some("Not logged in"));
+
+        let response = GetMarketResponse::from(Bytes::from_static(br#"{"success": false}"#));
+
+        let err = response.deserialize().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::RejectedByExchange);
+        assert_eq!(err.exchange_message(), None);
+    }
+
+    #[test]
+    fn retry_request_is_retryable() {
+        let response = GetMarketResponse::from(Bytes::from_static(
+            br#"{"success": false, "error": "Please retry request"}"#,
+        ));
+
+        let err = response.deserialize().unwrap_err();
+        assert!(err.is_retryable());
+        assert_eq!(err.rejection_reason(), None);
+        assert_eq!(err.exchange_message(), Some("Please retry request"));
+
+        let response = GetMarketResponse::from(Bytes::from_static(
+            br#"{"success": false, "error": "No such market: BTC-PERPS"}"#,
+        ));
+
+        assert!(!response.deserialize().unwrap_err().is_retryable());
+
+        // The request may have been executed before these.
+        assert!(!Error::new(ErrorKind::RequestExecutionFailed(None)).is_retryable());
+        assert!(!Error::new(ErrorKind::RequestExecutionFailed(Some(
+            http::StatusCode::SERVICE_UNAVAILABLE
+        )))
+        .is_retryable());
+    }
 
-        params.push(("bread", "baguette".into()));
+    #[test]
+    fn is_empty() {
+        let response = GetFundingRatesResponse::from(Bytes::from_static(
+            br#"{"success": true, "result": [ ]}"#,
+        ));
+
+        assert!(response.is_empty().unwrap());
+
+        let response = GetFundingRatesResponse::from(Bytes::from_static(
+            br#"{"success": true, "result": [{"future": "BTC-PERP", "rate": 0.0025, "time": "2019-06-02T08:00:00+00:00"}]}"#,
+        ));
+
+        assert!(!response.is_empty().unwrap());
+    }
+
+    #[test]
+    fn rate_limit_class() {
+        let order = PlaceOrder {
+            market: "BTC-PERP",
+            side: Side::Buy,
+            price: None,
+            size: PositiveDecimal::new(Decimal::ONE).unwrap(),
+            client_id: None,
+            opts: None,
+        };
+
+        assert_eq!(order.rate_limit_class(), RateLimitClass::Orders);
+        assert_eq!(
+            CancelOrder {
+                order_id: OrderId::Exchange(1),
+            }
+            .rate_limit_class(),
+            RateLimitClass::Orders
+        );
+        assert_eq!(
+            CancelTriggerOrder { order_id: 1 }.rate_limit_class(),
+            RateLimitClass::Orders
+        );
+        assert_eq!(
+            GetOpenOrders::all().rate_limit_class(),
+            RateLimitClass::NonOrders
+        );
+        assert_eq!(
+            GetOpenTriggerOrders {
+                market: None,
+                r#type: None
+            }
+            .rate_limit_class(),
+            RateLimitClass::NonOrders
+        );
+        assert_eq!(GetMarkets.rate_limit_class(), RateLimitClass::NonOrders);
+    }
+
+    #[test]
+    fn is_idempotent() {
+        let order = PlaceOrder {
+            market: "BTC-PERP",
+            side: Side::Buy,
+            price: None,
+            size: PositiveDecimal::new(Decimal::ONE).unwrap(),
+            client_id: None,
+            opts: None,
+        };
+
+        assert!(!order.is_idempotent());
+        assert!(PlaceOrder {
+            client_id: Some("my-order"),
+            ..order
+        }
+        .is_idempotent());
+
+        assert!(GetMarkets.is_idempotent());
+        assert!(CancelOrder {
+            order_id: OrderId::Exchange(1),
+        }
+        .is_idempotent());
+    }
+
+    #[test]
+    fn url_encoding_works() {
+        let params: Vec<(&'static str, String)> = vec![("bread", "baguette".into())];
 
         assert_eq!(
             &serde_urlencoded::to_string(params).unwrap(),
             "bread=baguette"
         );
     }
+
+    #[test]
+    fn written_query_matches_query_params() {
+        let start_time = Some(UnixTimestamp::new(1648996980000));
+        let end_time = Some(UnixTimestamp::new(1648999860000));
+
+        assert_query_matches_params(&GetCandles {
+            market: "BTC-PERP",
+            resolution: WindowLength::OneMinute,
+            start_time,
+            end_time,
+        });
+        assert_query_matches_params(&GetFills {
+            market: Some("BTC/USD"),
+            start_time,
+            end_time: None,
+            order_id: Some(8436981),
+            order: Some(SortOrder::Ascending),
+        });
+        assert_query_matches_params(&GetFills {
+            market: None,
+            start_time: None,
+            end_time: None,
+            order_id: None,
+            order: None,
+        });
+        assert_query_matches_params(&GetOrderHistory {
+            market: Some("BTC-PERP"),
+            side: Some(Side::Sell),
+            order_type: None,
+            start_time,
+            end_time,
+        });
+        assert_query_matches_params(&GetOpenTriggerOrders {
+            market: Some("BTC-PERP"),
+            r#type: Some(TriggerOrderType::Stop),
+        });
+        assert_query_matches_params(&GetTriggerOrderHistory {
+            market: Some("BTC-PERP"),
+            side: None,
+            r#type: Some(TriggerOrderType::TrailingStop),
+            order_type: Some(OrderType::Market),
+            start_time,
+            end_time: None,
+        });
+    }
 }
@@ -1,14 +1,16 @@
-use std::{borrow::Cow, convert::TryFrom};
+use std::{borrow::Cow, collections::BTreeMap, convert::TryFrom, error::Error as StdError, fmt};
 
 use bytes::Bytes;
-use reqwest::Method;
+use http::Method;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use time::OffsetDateTime;
 
 use crate::{
     data::{CancelAckMsg, FtxDateTime, PositiveDecimal, Side, UnixTimestamp},
     private::Sealed,
-    Json, OptJson, QueryParams, Request,
+    Json, OptJson, QueryParams, QueryWriter, Request,
 };
 
 use super::macros::response;
@@ -78,6 +80,14 @@ pub enum OrderStatus {
     Closed,
 }
 
+impl OrderStatus {
+    /// Whether the order can no longer change, i.e. it has been filled
+    /// or cancelled.
+    pub fn is_terminal(&self) -> bool {
+        *self == Self::Closed
+    }
+}
+
 /// Type of order id.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum OrderId<'a> {
@@ -88,6 +98,34 @@ pub enum OrderId<'a> {
     Client(&'a str),
 }
 
+impl<'a> OrderId<'a> {
+    /// An order id issued by the exchange.
+    pub const fn exchange(id: u64) -> Self {
+        Self::Exchange(id)
+    }
+
+    /// An order id requested by the client.
+    pub const fn client(id: &'a str) -> Self {
+        Self::Client(id)
+    }
+
+    /// Guess the kind of id from its format: a string of digits which
+    /// fits in a `u64` is taken as an exchange id, anything else as a
+    /// client id.
+    ///
+    /// Client ids are free-form, so a purely numeric client id will be
+    /// misclassified. Use [`OrderId::client`] when the kind is known.
+    pub fn parse(s: &'a str) -> Self {
+        if !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit()) {
+            if let Ok(id) = s.parse() {
+                return Self::Exchange(id);
+            }
+        }
+
+        Self::Client(s)
+    }
+}
+
 /// Order edit options.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -102,6 +140,7 @@ pub struct EditOrderOpts<'a> {
 
 /// Available order options.
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct OrderOpts {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ioc: Option<bool>,
@@ -121,6 +160,20 @@ pub struct GetOpenOrders<'a> {
     pub market: Option<&'a str>,
 }
 
+impl<'a> GetOpenOrders<'a> {
+    /// Open orders across all markets.
+    pub fn all() -> Self {
+        Self { market: None }
+    }
+
+    /// Open orders in a single market.
+    pub fn for_market(market: &'a str) -> Self {
+        Self {
+            market: Some(market),
+        }
+    }
+}
+
 impl<'a> Sealed for GetOpenOrders<'a> {}
 
 impl<'a> Request<true> for GetOpenOrders<'a> {
@@ -188,6 +241,24 @@ impl<'a> Request<true> for GetOrderHistory<'a> {
 
         Some(params)
     }
+
+    fn write_query(&self, query: &mut QueryWriter<'_>) {
+        if let Some(market) = self.market {
+            query.append("market", market);
+        }
+        if let Some(side) = self.side {
+            query.append("side", side.as_param());
+        }
+        if let Some(order_type) = self.order_type {
+            query.append("orderType", order_type.as_param());
+        }
+        if let Some(start_time) = self.start_time {
+            query.append_uint("start_time", start_time.get());
+        }
+        if let Some(end_time) = self.end_time {
+            query.append_uint("end_time", end_time.get());
+        }
+    }
 }
 
 pub struct GetOrderHistoryResponse(Bytes);
@@ -223,6 +294,7 @@ impl<'a> Request<true> for GetOrderStatus<'a> {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct GetOrderStatusResponse(Bytes);
 
 response!(GetOrderStatusResponse, Order<'a>, OrderPartial<'a>);
@@ -253,6 +325,12 @@ impl<'a> Request<true> for PlaceOrder<'a> {
     fn to_json(&self) -> Option<Result<String, serde_json::Error>> {
         Some(serde_json::to_string(self))
     }
+
+    /// The exchange rejects a second order with the same client id, so
+    /// placement is only safe to retry if one is set.
+    fn is_idempotent(&self) -> bool {
+        self.client_id.is_some()
+    }
 }
 
 pub struct PlaceOrderResponse(Bytes);
@@ -335,6 +413,105 @@ pub struct CancelAllOrders<'a> {
     pub limit_orders_only: Option<bool>,
 }
 
+impl<'a> CancelAllOrders<'a> {
+    /// Cancel every open order across all markets.
+    pub fn all() -> Self {
+        Self {
+            market: None,
+            side: None,
+            limit_orders_only: None,
+        }
+    }
+
+    /// Cancel every open order in a single market.
+    pub fn for_market(market: &'a str) -> Self {
+        Self {
+            market: Some(market),
+            ..Self::all()
+        }
+    }
+
+    /// Build a request which is checked before use. Unlike setting the
+    /// fields directly, cancelling across all markets must be
+    /// explicitly confirmed.
+    pub fn builder() -> CancelAllOrdersBuilder<'a> {
+        CancelAllOrdersBuilder::default()
+    }
+}
+
+/// Builder for [`CancelAllOrders`], see [`CancelAllOrders::builder`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CancelAllOrdersBuilder<'a> {
+    market: Option<&'a str>,
+    side: Option<Side>,
+    limit_orders_only: Option<bool>,
+    all_markets: bool,
+}
+
+impl<'a> CancelAllOrdersBuilder<'a> {
+    pub fn market(mut self, market: &'a str) -> Self {
+        self.market = Some(market);
+        self
+    }
+
+    pub fn side(mut self, side: Side) -> Self {
+        self.side = Some(side);
+        self
+    }
+
+    pub fn limit_orders_only(mut self, limit_orders_only: bool) -> Self {
+        self.limit_orders_only = Some(limit_orders_only);
+        self
+    }
+
+    /// Confirm that orders should be cancelled in every market. Required
+    /// if no market is set.
+    pub fn confirm_all_markets(mut self) -> Self {
+        self.all_markets = true;
+        self
+    }
+
+    pub fn build(self) -> Result<CancelAllOrders<'a>, CancelAllOrdersError> {
+        match (self.market, self.all_markets) {
+            (None, false) => return Err(CancelAllOrdersError::AllMarketsNotConfirmed),
+            (Some(_), true) => return Err(CancelAllOrdersError::MarketWithAllMarkets),
+            _ => (),
+        }
+
+        Ok(CancelAllOrders {
+            market: self.market,
+            side: self.side,
+            limit_orders_only: self.limit_orders_only,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CancelAllOrdersError {
+    /// No market was set and cancelling across all markets wasn't
+    /// confirmed.
+    AllMarketsNotConfirmed,
+    /// A market was set but cancelling across all markets was also
+    /// confirmed.
+    MarketWithAllMarkets,
+}
+
+impl fmt::Display for CancelAllOrdersError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AllMarketsNotConfirmed => {
+                f.write_str("no market set and cancelling in all markets not confirmed")
+            }
+            Self::MarketWithAllMarkets => {
+                f.write_str("market set but cancelling in all markets confirmed")
+            }
+        }
+    }
+}
+
+impl StdError for CancelAllOrdersError {}
+
 impl<'a> Sealed for CancelAllOrders<'a> {}
 
 impl<'a> Request<true> for CancelAllOrders<'a> {
@@ -360,6 +537,8 @@ pub struct Order<'a> {
     pub id: u64,
     pub client_id: Option<&'a str>,
     pub market: &'a str,
+    /// Set to the same value as `market` for derivatives orders, `None`
+    /// for spot orders.
     pub future: Option<&'a str>,
     pub side: Side,
     pub size: Decimal,
@@ -376,6 +555,59 @@ pub struct Order<'a> {
     pub created_at: FtxDateTime,
 }
 
+/// Serialized names of the fields of [`Order`].
+const ORDER_FIELDS: [&str; 17] = [
+    "id",
+    "clientId",
+    "market",
+    "future",
+    "side",
+    "size",
+    "price",
+    "avgFillPrice",
+    "filledSize",
+    "remainingSize",
+    "type",
+    "status",
+    "reduceOnly",
+    "ioc",
+    "postOnly",
+    "liquidation",
+    "createdAt",
+];
+
+impl<'a> Order<'a> {
+    /// Whether the order is for a future rather than a spot market.
+    pub fn is_derivative(&self) -> bool {
+        self.future.is_some()
+    }
+
+    /// The serialized fields of the order keyed by name, e.g. for tabular
+    /// logging or diffing two orders.
+    pub fn as_field_map(&self) -> Result<BTreeMap<&'static str, Value>, serde_json::Error> {
+        let mut fields = match serde_json::to_value(self)? {
+            Value::Object(fields) => fields,
+            _ => unreachable!("orders serialize to a JSON object"),
+        };
+
+        Ok(ORDER_FIELDS
+            .iter()
+            .filter_map(|&name| Some((name, fields.remove(name)?)))
+            .collect())
+    }
+}
+
+/// Orders created before `cutoff`, e.g. to find stale resting orders.
+pub fn orders_created_before<'a, 'b>(
+    orders: &'b [Order<'a>],
+    cutoff: OffsetDateTime,
+) -> Vec<&'b Order<'a>> {
+    orders
+        .iter()
+        .filter(|o| o.created_at.get() < cutoff)
+        .collect()
+}
+
 impl<'a> TryFrom<OrderPartial<'a>> for Order<'a> {
     type Error = serde_json::Error;
 
@@ -462,6 +694,16 @@ pub struct OrderPlaced<'a> {
     pub created_at: FtxDateTime,
 }
 
+impl<'a> OrderPlaced<'a> {
+    /// Whether this is a liquidation order. Unlike [`Order`], the exchange
+    /// may return `null` for this flag when acknowledging a newly placed or
+    /// modified order, since at that point it has only been accepted and
+    /// not yet processed. A missing flag is treated as `false`.
+    pub fn is_liquidation(&self) -> bool {
+        self.liquidation.unwrap_or(false)
+    }
+}
+
 impl<'a> TryFrom<OrderPlacedPartial<'a>> for OrderPlaced<'a> {
     type Error = serde_json::Error;
 
@@ -529,10 +771,64 @@ pub struct OrderPlacedPartial<'a> {
 mod tests {
     use std::convert::TryInto;
 
-    use crate::Response;
+    use crate::{error::RejectionReason, Response};
 
     use super::*;
 
+    #[test]
+    fn open_orders_market_selection() {
+        assert_eq!(GetOpenOrders::all().query_params(), None);
+        assert_eq!(
+            GetOpenOrders::for_market("XRP-PERP").query_params(),
+            Some(vec![("market", "XRP-PERP".into())])
+        );
+
+        assert_eq!(CancelAllOrders::all().to_json().unwrap().unwrap(), "{}");
+        assert_eq!(
+            CancelAllOrders::for_market("XRP-PERP")
+                .to_json()
+                .unwrap()
+                .unwrap(),
+            r#"{"market":"XRP-PERP"}"#
+        );
+    }
+
+    #[test]
+    fn cancel_all_orders_builder() {
+        assert_eq!(
+            CancelAllOrders::builder().side(Side::Buy).build(),
+            Err(CancelAllOrdersError::AllMarketsNotConfirmed)
+        );
+        assert_eq!(
+            CancelAllOrders::builder()
+                .market("XRP-PERP")
+                .confirm_all_markets()
+                .build(),
+            Err(CancelAllOrdersError::MarketWithAllMarkets)
+        );
+
+        assert_eq!(
+            CancelAllOrders::builder()
+                .side(Side::Buy)
+                .confirm_all_markets()
+                .build(),
+            Ok(CancelAllOrders {
+                side: Some(Side::Buy),
+                ..CancelAllOrders::all()
+            })
+        );
+        assert_eq!(
+            CancelAllOrders::builder()
+                .market("XRP-PERP")
+                .limit_orders_only(true)
+                .build(),
+            Ok(CancelAllOrders {
+                limit_orders_only: Some(true),
+                ..CancelAllOrders::for_market("XRP-PERP")
+            })
+        );
+    }
+
     #[test]
     fn get_open_orders() {
         let json = r#"
@@ -571,6 +867,105 @@ mod tests {
             .collect();
 
         assert_eq!(response.deserialize().unwrap(), from_partial);
+
+        assert!(from_partial[0].is_derivative());
+        assert!(!Order {
+            market: "XRP/USD",
+            future: None,
+            ..from_partial[0]
+        }
+        .is_derivative());
+    }
+
+    const ORDER_JSON: &str = r#"
+{
+  "createdAt": "2019-03-05T09:56:55.728933+00:00",
+  "filledSize": 10,
+  "future": "XRP-PERP",
+  "id": 9596912,
+  "market": "XRP-PERP",
+  "price": 0.306525,
+  "avgFillPrice": 0.306526,
+  "remainingSize": 31421,
+  "side": "sell",
+  "size": 31431,
+  "status": "open",
+  "type": "limit",
+  "reduceOnly": false,
+  "ioc": false,
+  "postOnly": false,
+  "clientId": null,
+  "liquidation": false
+}
+"#;
+
+    #[test]
+    fn price_keeps_exact_decimals() {
+        let partial: OrderPartial<'_> = serde_json::from_str(ORDER_JSON).unwrap();
+
+        let price = partial.price.deserialize().unwrap();
+        assert_eq!(price.scale(), 6);
+        assert_eq!(price.to_string(), "0.306525");
+        // Decimals serialize as strings, the raw JSON is passed through.
+        assert_eq!(serde_json::to_string(&price).unwrap(), r#""0.306525""#);
+        assert_eq!(serde_json::to_string(&partial.price).unwrap(), "0.306525");
+
+        let order = Order::try_from(partial).unwrap();
+        assert_eq!(order.price, price);
+
+        // Trailing zeros are kept too.
+        let json = ORDER_JSON.replace("0.306525", "0.306500");
+        let partial: OrderPartial<'_> = serde_json::from_str(&json).unwrap();
+
+        let price = partial.price.deserialize().unwrap();
+        assert_eq!(price.scale(), 6);
+        assert_eq!(serde_json::to_string(&price).unwrap(), r#""0.306500""#);
+    }
+
+    #[test]
+    fn order_field_map() {
+        let order: Order<'_> = serde_json::from_str(ORDER_JSON).unwrap();
+        let fields = order.as_field_map().unwrap();
+
+        assert_eq!(fields["id"], order.id);
+        assert_eq!(fields["price"], serde_json::to_value(order.price).unwrap());
+        assert_eq!(fields["status"], "open");
+
+        // Every serialized field should be listed.
+        assert_eq!(
+            fields.len(),
+            serde_json::to_value(order)
+                .unwrap()
+                .as_object()
+                .unwrap()
+                .len()
+        );
+    }
+
+    #[test]
+    fn created_before() {
+        use time::macros::datetime;
+
+        let order: Order<'_> = serde_json::from_str(ORDER_JSON).unwrap();
+
+        let newer = Order {
+            id: 9596913,
+            created_at: datetime!(2019-03-05 10:30 UTC).into(),
+            ..order
+        };
+
+        let orders = [order, newer];
+
+        let ids = |cutoff| {
+            orders_created_before(&orders, cutoff)
+                .into_iter()
+                .map(|o| o.id)
+                .collect::<Vec<_>>()
+        };
+
+        assert!(ids(datetime!(2019-03-05 09:00 UTC)).is_empty());
+        assert_eq!(ids(datetime!(2019-03-05 10:00 UTC)), [9596912]);
+        assert_eq!(ids(datetime!(2019-03-05 11:00 UTC)), [9596912, 9596913]);
     }
 
     #[test]
@@ -646,6 +1041,136 @@ mod tests {
             response.deserialize_partial().unwrap().try_into().unwrap();
 
         assert_eq!(response.deserialize().unwrap(), from_partial);
+
+        assert!(!from_partial.is_liquidation());
+        assert!(!OrderPlaced {
+            liquidation: None,
+            ..from_partial
+        }
+        .is_liquidation());
+        assert!(OrderPlaced {
+            liquidation: Some(true),
+            ..from_partial
+        }
+        .is_liquidation());
+    }
+
+    #[test]
+    fn order_opts_serialization() {
+        let order = PlaceOrder {
+            market: "XRP-PERP",
+            side: Side::Sell,
+            price: Some("0.306525".parse().unwrap()),
+            size: "31431".parse().unwrap(),
+            client_id: None,
+            opts: Some(OrderOpts {
+                post_only: Some(true),
+                ..Default::default()
+            }),
+        };
+
+        assert_eq!(
+            order.to_json().unwrap().unwrap(),
+            r#"{"market":"XRP-PERP","side":"sell","price":"0.306525","size":"31431","postOnly":true}"#
+        );
+    }
+
+    // FTX expects camelCase option names and silently ignores others, so
+    // e.g. `post_only` sent as is placed an order that could take.
+    #[test]
+    fn order_opts_camel_case() {
+        let opts = OrderOpts {
+            ioc: Some(false),
+            post_only: Some(true),
+            reduce_only: Some(true),
+            reject_on_price_band: Some(false),
+            reject_after_ts: Some(UnixTimestamp::new(1648996980000)),
+        };
+
+        assert_eq!(
+            serde_json::to_string(&opts).unwrap(),
+            r#"{"ioc":false,"postOnly":true,"reduceOnly":true,"rejectOnPriceBand":false,"rejectAfterTs":1648996980000}"#
+        );
+    }
+
+    // The request is signed over this exact string, so any change to the
+    // field order, e.g. from the flattened `opts`, must be deliberate.
+    #[test]
+    fn place_order_body_is_stable() {
+        let order = PlaceOrder {
+            market: "BTC-PERP",
+            side: Side::Buy,
+            price: Some("8500".parse().unwrap()),
+            size: "1".parse().unwrap(),
+            client_id: Some("my-order"),
+            opts: Some(OrderOpts {
+                ioc: Some(false),
+                post_only: Some(true),
+                reduce_only: Some(false),
+                reject_on_price_band: Some(true),
+                reject_after_ts: Some(UnixTimestamp::new(1588591856950)),
+            }),
+        };
+
+        let body = order.to_json().unwrap().unwrap();
+
+        assert_eq!(
+            body,
+            concat!(
+                r#"{"market":"BTC-PERP","side":"buy","price":"8500","size":"1","#,
+                r#""clientId":"my-order","ioc":false,"postOnly":true,"reduceOnly":false,"#,
+                r#""rejectOnPriceBand":true,"rejectAfterTs":1588591856950}"#
+            )
+        );
+        assert_eq!(order.to_json().unwrap().unwrap(), body);
+    }
+
+    #[test]
+    fn post_only_rejection() {
+        let response = PlaceOrderResponse(
+            r#"{"success": false, "error": "Post only order would be taken"}"#
+                .as_bytes()
+                .into(),
+        );
+
+        assert_eq!(
+            response.deserialize().unwrap_err().rejection_reason(),
+            Some(RejectionReason::PostOnlyWouldTake)
+        );
+
+        // Other messages mentioning post only orders aren't this rejection.
+        let response = PlaceOrderResponse(
+            r#"{"success": false, "error": "Market is in post-only mode"}"#
+                .as_bytes()
+                .into(),
+        );
+
+        assert_eq!(
+            response.deserialize().unwrap_err().rejection_reason(),
+            Some(RejectionReason::Other)
+        );
+
+        let response = PlaceOrderResponse(
+            r#"{"success": false, "error": "Size too small for provide"}"#
+                .as_bytes()
+                .into(),
+        );
+
+        assert_eq!(
+            response.deserialize().unwrap_err().rejection_reason(),
+            Some(RejectionReason::SizeTooSmall)
+        );
+
+        let response = PlaceOrderResponse(
+            r#"{"success": false, "error": "Not enough balances"}"#
+                .as_bytes()
+                .into(),
+        );
+
+        assert_eq!(
+            response.deserialize().unwrap_err().rejection_reason(),
+            Some(RejectionReason::Other)
+        );
     }
 
     #[test]
@@ -715,6 +1240,20 @@ mod tests {
         assert_eq!(response.deserialize().unwrap(), from_partial);
     }
 
+    #[test]
+    fn parse_order_id() {
+        assert_eq!(OrderId::parse("9596912"), OrderId::exchange(9596912));
+        assert_eq!(OrderId::parse("my-order-1"), OrderId::client("my-order-1"));
+
+        // Too large for an exchange id.
+        assert_eq!(
+            OrderId::parse("99999999999999999999"),
+            OrderId::Client("99999999999999999999")
+        );
+        assert_eq!(OrderId::parse("+1"), OrderId::Client("+1"));
+        assert_eq!(OrderId::parse(""), OrderId::Client(""));
+    }
+
     #[test]
     fn cancel_order() {
         let json = r#"
@@ -0,0 +1,105 @@
+use bytes::Bytes;
+use http::Method;
+use serde_json::value::RawValue;
+use std::borrow::Cow;
+
+use crate::{private::Sealed, QueryParams, Request};
+
+use super::macros::response;
+
+/// A request to an arbitrary endpoint, for those not yet modelled by
+/// the crate. Execute with `Client::execute_raw` or
+/// `AuthClient::execute_raw`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawRequest<'a> {
+    pub method: Method,
+    /// Path relative to the API root, e.g. `/markets`.
+    pub path: Cow<'a, str>,
+    pub query: Option<QueryParams>,
+    /// JSON encoded body.
+    pub body: Option<String>,
+    /// Whether the request should be signed.
+    pub auth: bool,
+}
+
+impl<'a> Sealed for RawRequest<'a> {}
+
+impl<'a, const AUTH: bool> Request<AUTH> for RawRequest<'a> {
+    const PATH: &'static str = "";
+
+    // Overridden by `method`.
+    const METHOD: Method = Method::GET;
+
+    type Response = RawResponse;
+
+    fn method(&self) -> Method {
+        self.method.clone()
+    }
+
+    fn path(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.path)
+    }
+
+    fn query_params(&self) -> Option<QueryParams> {
+        self.query.clone()
+    }
+
+    fn to_json(&self) -> Option<Result<String, serde_json::Error>> {
+        self.body.clone().map(Ok)
+    }
+}
+
+/// The response to a [`RawRequest`]. Deserializes to the unparsed JSON
+/// of the `result` field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawResponse(Bytes);
+
+response!(RawResponse, &'a RawValue, &'a RawValue);
+
+#[cfg(test)]
+mod tests {
+    use crate::Response;
+
+    use super::*;
+
+    #[test]
+    fn raw_request() {
+        let request = RawRequest {
+            method: Method::GET,
+            path: "/markets/BTC-PERP/orderbook".into(),
+            query: Some(vec![("depth", "20".into())]),
+            body: None,
+            auth: false,
+        };
+
+        assert_eq!(Request::<false>::method(&request), Method::GET);
+        assert_eq!(
+            Request::<false>::path(&request),
+            "/markets/BTC-PERP/orderbook"
+        );
+        assert_eq!(
+            Request::<false>::query_params(&request),
+            Some(vec![("depth", "20".into())])
+        );
+    }
+
+    #[test]
+    fn raw_response() {
+        let json = r#"
+{
+  "success": true,
+  "result": {
+    "asks": [[4114.25, 6.263]],
+    "bids": [[4112.25, 49.29]]
+  }
+}
+"#;
+        let response = RawResponse(json.as_bytes().into());
+
+        let result = response.deserialize().unwrap();
+
+        let value: serde_json::Value = serde_json::from_str(result.get()).unwrap();
+
+        assert_eq!(value["asks"][0][1], serde_json::json!(6.263));
+    }
+}
@@ -1,14 +1,14 @@
 use std::convert::TryFrom;
 
 use bytes::Bytes;
-use reqwest::Method;
+use http::Method;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
 use crate::{
     data::{FtxDateTime, UnixTimestamp},
     private::Sealed,
-    Json, OptJson, Request,
+    Json, OptJson, QueryWriter, Request,
 };
 
 use super::macros::response;
@@ -143,6 +143,15 @@ impl Request<true> for GetBorrowHistory {
 
         Some(params)
     }
+
+    fn write_query(&self, query: &mut QueryWriter<'_>) {
+        if let Some(start_time) = self.start_time {
+            query.append_uint("start_time", start_time.get());
+        }
+        if let Some(end_time) = self.end_time {
+            query.append_uint("end_time", end_time.get());
+        }
+    }
 }
 
 pub struct GetBorrowHistoryResponse(Bytes);
@@ -372,6 +381,26 @@ mod tests {
         assert_eq!(response.deserialize().unwrap(), from_partial);
     }
 
+    #[test]
+    fn borrow_rate_scientific_notation_round_trip() {
+        let json =
+            r#"{"coin": "BTC", "estimate": 1.45e-06, "previous": 1.027E-5, "average24hr": 1e-06}"#;
+
+        let rate: BorrowRate = serde_json::from_str(json).unwrap();
+
+        assert_eq!(rate.estimate, Decimal::new(145, 8));
+        assert_eq!(rate.previous, Decimal::new(1027, 8));
+        assert_eq!(rate.average_24hr, Some(Decimal::new(1, 6)));
+
+        let serialized = serde_json::to_string(&rate).unwrap();
+        assert!(!serialized.contains("e-"));
+
+        assert_eq!(
+            serde_json::from_str::<BorrowRate>(&serialized).unwrap(),
+            rate
+        );
+    }
+
     #[test]
     fn get_lending_rates() {
         let json = r#"
@@ -1,7 +1,7 @@
 use std::convert::TryFrom;
 
 use bytes::Bytes;
-use reqwest::Method;
+use http::Method;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
@@ -64,6 +64,28 @@ pub struct LatencyStats {
     pub success_p50: Option<Decimal>,
 }
 
+impl LatencyStats {
+    /// The fraction of requests which succeeded, or `None` if there were
+    /// no requests.
+    pub fn success_rate(&self) -> Option<Decimal> {
+        success_rate(self.success_count, self.request_count)
+    }
+}
+
+/// The fraction of requests which succeeded across all of `stats`, or
+/// `None` if there were no requests.
+pub fn overall_success_rate(stats: &[LatencyStats]) -> Option<Decimal> {
+    let (success_count, request_count) = stats.iter().fold((0, 0), |(success, total), s| {
+        (success + s.success_count, total + s.request_count)
+    });
+
+    success_rate(success_count, request_count)
+}
+
+fn success_rate(success_count: u64, request_count: u64) -> Option<Decimal> {
+    Decimal::from(success_count).checked_div(Decimal::from(request_count))
+}
+
 impl<'a> TryFrom<LatencyStatsPartial<'a>> for LatencyStats {
     type Error = serde_json::Error;
 
@@ -140,5 +162,13 @@ mod tests {
             .collect();
 
         assert_eq!(response.deserialize().unwrap(), from_partial);
+
+        assert_eq!(from_partial[0].success_rate(), Some(Decimal::ZERO));
+        assert_eq!(from_partial[1].success_rate(), Some(Decimal::ONE));
+        assert_eq!(
+            overall_success_rate(&from_partial),
+            Some(Decimal::from(27) / Decimal::from(70))
+        );
+        assert_eq!(overall_success_rate(&[]), None);
     }
 }
@@ -1,8 +1,8 @@
 use bytes::Bytes;
-use reqwest::Method;
+use http::Method;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
-use std::{borrow::Cow, convert::TryFrom};
+use std::{borrow::Cow, convert::TryFrom, error::Error as StdError, fmt};
 
 use crate::{data::FtxDateTime, private::Sealed, Json, Request};
 
@@ -180,6 +180,42 @@ impl<'a> Request<true> for TransferBetweenSubaccounts<'a> {
     }
 }
 
+impl<'a> TransferBetweenSubaccounts<'a> {
+    /// Check the transfer can be covered by `available`, the amount of
+    /// `coin` available for withdrawal from the source account.
+    pub fn check_available(&self, available: Decimal) -> Result<(), InsufficientBalanceError> {
+        if self.size > available {
+            return Err(InsufficientBalanceError {
+                coin: self.coin.to_owned(),
+                size: self.size,
+                available,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InsufficientBalanceError {
+    pub coin: String,
+    pub size: Decimal,
+    pub available: Decimal,
+}
+
+impl fmt::Display for InsufficientBalanceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "cannot transfer {} {}, only {} is available for withdrawal",
+            self.size, self.coin, self.available
+        )
+    }
+}
+
+impl StdError for InsufficientBalanceError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TransferBetweenSubaccountsResponse(Bytes);
 
 response!(
@@ -390,6 +426,9 @@ mod tests {
             .unwrap();
     }
 
+    // Kept in the same shape as the other endpoint tests, even though the
+    // data is `()`.
+    #[allow(clippy::unit_cmp)]
     #[test]
     fn delete_subaccount() {
         let json = r#"
@@ -435,6 +474,25 @@ mod tests {
         assert_eq!(response.deserialize().unwrap(), from_partial);
     }
 
+    #[test]
+    fn check_transfer_balance() {
+        let transfer = TransferBetweenSubaccounts {
+            coin: "USDT",
+            size: Decimal::new(2320, 0),
+            source: Some("sub"),
+            destination: None,
+        };
+
+        assert_eq!(transfer.check_available(Decimal::new(23202, 1)), Ok(()));
+        assert_eq!(transfer.check_available(Decimal::new(2320, 0)), Ok(()));
+
+        let err = transfer.check_available(Decimal::new(2319, 0)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "cannot transfer 2320 USDT, only 2319 is available for withdrawal"
+        );
+    }
+
     #[test]
     fn transfer_between_subaccounts() {
         let json = r#"
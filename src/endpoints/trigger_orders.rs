@@ -0,0 +1,888 @@
+use std::{borrow::Cow, convert::TryFrom};
+
+use bytes::Bytes;
+use http::Method;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize, Serializer};
+
+use crate::{
+    data::{CancelAckMsg, FtxDateTime, PositiveDecimal, Side, UnixTimestamp},
+    private::Sealed,
+    Json, OptJson, QueryParams, QueryWriter, Request,
+};
+
+use super::{macros::response, orders::OrderType};
+
+macro_rules! get_trigger_order_triggers_path {
+    () => {
+        "/conditional_orders/{order_id}/triggers"
+    };
+}
+
+macro_rules! modify_trigger_order_path {
+    () => {
+        "/conditional_orders/{order_id}/modify"
+    };
+}
+
+macro_rules! cancel_trigger_order_path {
+    () => {
+        "/conditional_orders/{order_id}"
+    };
+}
+
+/// Trigger order type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TriggerOrderType {
+    #[serde(rename = "stop")]
+    Stop,
+    #[serde(rename = "trailing_stop")]
+    TrailingStop,
+    #[serde(rename = "take_profit")]
+    TakeProfit,
+}
+
+impl TriggerOrderType {
+    pub fn as_param(&self) -> &str {
+        match self {
+            Self::Stop => "stop",
+            Self::TrailingStop => "trailing_stop",
+            Self::TakeProfit => "take_profit",
+        }
+    }
+}
+
+// Placing an order takes the type in camel case, unlike the query
+// parameter and responses.
+fn serialize_place_type<S>(r#type: &TriggerOrderType, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(match r#type {
+        TriggerOrderType::Stop => "stop",
+        TriggerOrderType::TrailingStop => "trailingStop",
+        TriggerOrderType::TakeProfit => "takeProfit",
+    })
+}
+
+/// Trigger order status.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TriggerOrderStatus {
+    #[serde(rename = "open")]
+    Open,
+    #[serde(rename = "cancelled")]
+    Cancelled,
+    #[serde(rename = "triggered")]
+    Triggered,
+}
+
+/// Retrieve all open trigger orders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GetOpenTriggerOrders<'a> {
+    pub market: Option<&'a str>,
+    pub r#type: Option<TriggerOrderType>,
+}
+
+impl<'a> Sealed for GetOpenTriggerOrders<'a> {}
+
+impl<'a> Request<true> for GetOpenTriggerOrders<'a> {
+    const PATH: &'static str = "/conditional_orders";
+
+    const METHOD: Method = Method::GET;
+
+    type Response = GetOpenTriggerOrdersResponse;
+
+    fn query_params(&self) -> Option<QueryParams> {
+        if self.market.is_none() && self.r#type.is_none() {
+            return None;
+        }
+
+        let mut params = Vec::with_capacity(2);
+
+        if let Some(market) = self.market {
+            params.push(("market", market.into()));
+        }
+        if let Some(r#type) = self.r#type {
+            params.push(("type", r#type.as_param().into()))
+        }
+
+        Some(params)
+    }
+}
+
+pub struct GetOpenTriggerOrdersResponse(Bytes);
+
+response!(
+    GetOpenTriggerOrdersResponse,
+    Vec<TriggerOrder<'a>>,
+    Vec<TriggerOrderPartial<'a>>
+);
+
+/// Retrieve the orders placed each time a trigger order was triggered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GetTriggerOrderTriggers {
+    pub order_id: u64,
+}
+
+impl Sealed for GetTriggerOrderTriggers {}
+
+impl Request<true> for GetTriggerOrderTriggers {
+    const PATH: &'static str = get_trigger_order_triggers_path!();
+
+    const METHOD: Method = Method::GET;
+
+    type Response = GetTriggerOrderTriggersResponse;
+
+    fn path(&self) -> Cow<'_, str> {
+        Cow::Owned(format!(
+            get_trigger_order_triggers_path!(),
+            order_id = self.order_id
+        ))
+    }
+}
+
+pub struct GetTriggerOrderTriggersResponse(Bytes);
+
+response!(
+    GetTriggerOrderTriggersResponse,
+    Vec<TriggerOrderTrigger<'a>>,
+    Vec<TriggerOrderTriggerPartial<'a>>
+);
+
+/// Place a trigger order. `trigger_price` is required for stop and take
+/// profit orders and `trail_value`, negative for sells, for trailing
+/// stops. Set `order_price` to `None` to place a market order once
+/// triggered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaceTriggerOrder<'a> {
+    pub market: &'a str,
+    pub side: Side,
+    pub size: PositiveDecimal,
+    #[serde(serialize_with = "serialize_place_type")]
+    pub r#type: TriggerOrderType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trigger_price: Option<PositiveDecimal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order_price: Option<PositiveDecimal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trail_value: Option<Decimal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reduce_only: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_until_filled: Option<bool>,
+}
+
+impl<'a> Sealed for PlaceTriggerOrder<'a> {}
+
+impl<'a> Request<true> for PlaceTriggerOrder<'a> {
+    const PATH: &'static str = "/conditional_orders";
+
+    const METHOD: Method = Method::POST;
+
+    type Response = PlaceTriggerOrderResponse;
+
+    fn to_json(&self) -> Option<Result<String, serde_json::Error>> {
+        Some(serde_json::to_string(self))
+    }
+
+    /// There's no client id to deduplicate on, so placement is never
+    /// safe to retry.
+    fn is_idempotent(&self) -> bool {
+        false
+    }
+}
+
+pub struct PlaceTriggerOrderResponse(Bytes);
+
+response!(
+    PlaceTriggerOrderResponse,
+    TriggerOrderPlaced<'a>,
+    TriggerOrderPlacedPartial<'a>
+);
+
+/// Trigger order modification options.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModifyTriggerOrderOpts {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<PositiveDecimal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trigger_price: Option<PositiveDecimal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order_price: Option<PositiveDecimal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trail_value: Option<Decimal>,
+}
+
+/// Modify a trigger order. Exchange side this cancels the order and
+/// places a replacement with a new id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ModifyTriggerOrder {
+    pub order_id: u64,
+    pub opts: ModifyTriggerOrderOpts,
+}
+
+impl Sealed for ModifyTriggerOrder {}
+
+impl Request<true> for ModifyTriggerOrder {
+    const PATH: &'static str = modify_trigger_order_path!();
+
+    const METHOD: Method = Method::POST;
+
+    type Response = ModifyTriggerOrderResponse;
+
+    fn path(&self) -> Cow<'_, str> {
+        Cow::Owned(format!(
+            modify_trigger_order_path!(),
+            order_id = self.order_id
+        ))
+    }
+
+    fn to_json(&self) -> Option<Result<String, serde_json::Error>> {
+        Some(serde_json::to_string(&self.opts))
+    }
+}
+
+pub struct ModifyTriggerOrderResponse(Bytes);
+
+response!(
+    ModifyTriggerOrderResponse,
+    TriggerOrderPlaced<'a>,
+    TriggerOrderPlacedPartial<'a>
+);
+
+/// Cancel a trigger order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CancelTriggerOrder {
+    pub order_id: u64,
+}
+
+impl Sealed for CancelTriggerOrder {}
+
+impl Request<true> for CancelTriggerOrder {
+    const PATH: &'static str = cancel_trigger_order_path!();
+
+    const METHOD: Method = Method::DELETE;
+
+    type Response = CancelTriggerOrderResponse;
+
+    fn path(&self) -> Cow<'_, str> {
+        Cow::Owned(format!(
+            cancel_trigger_order_path!(),
+            order_id = self.order_id
+        ))
+    }
+}
+
+pub struct CancelTriggerOrderResponse(Bytes);
+
+response!(
+    CancelTriggerOrderResponse,
+    CancelAckMsg<'a>,
+    CancelAckMsg<'a>
+);
+
+/// Retrieve information on historical trigger orders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GetTriggerOrderHistory<'a> {
+    pub market: Option<&'a str>,
+    pub side: Option<Side>,
+    pub r#type: Option<TriggerOrderType>,
+    pub order_type: Option<OrderType>,
+    pub start_time: Option<UnixTimestamp>,
+    pub end_time: Option<UnixTimestamp>,
+}
+
+impl<'a> Sealed for GetTriggerOrderHistory<'a> {}
+
+impl<'a> Request<true> for GetTriggerOrderHistory<'a> {
+    const PATH: &'static str = "/conditional_orders/history";
+
+    const METHOD: Method = Method::GET;
+
+    type Response = GetTriggerOrderHistoryResponse;
+
+    fn query_params(&self) -> Option<QueryParams> {
+        if self.market.is_none()
+            && self.side.is_none()
+            && self.r#type.is_none()
+            && self.order_type.is_none()
+            && self.start_time.is_none()
+            && self.end_time.is_none()
+        {
+            return None;
+        }
+
+        let mut params = Vec::with_capacity(6);
+
+        if let Some(market) = self.market {
+            params.push(("market", market.into()));
+        }
+        if let Some(side) = self.side {
+            params.push(("side", side.as_param().into()))
+        }
+        if let Some(r#type) = self.r#type {
+            params.push(("type", r#type.as_param().into()))
+        }
+        if let Some(order_type) = self.order_type {
+            params.push(("orderType", order_type.as_param().into()))
+        }
+        if let Some(start_time) = self.start_time {
+            params.push(("start_time", start_time.get().to_string()))
+        }
+        if let Some(end_time) = self.end_time {
+            params.push(("end_time", end_time.get().to_string()))
+        }
+
+        Some(params)
+    }
+
+    fn write_query(&self, query: &mut QueryWriter<'_>) {
+        if let Some(market) = self.market {
+            query.append("market", market);
+        }
+        if let Some(side) = self.side {
+            query.append("side", side.as_param());
+        }
+        if let Some(r#type) = self.r#type {
+            query.append("type", r#type.as_param());
+        }
+        if let Some(order_type) = self.order_type {
+            query.append("orderType", order_type.as_param());
+        }
+        if let Some(start_time) = self.start_time {
+            query.append_uint("start_time", start_time.get());
+        }
+        if let Some(end_time) = self.end_time {
+            query.append_uint("end_time", end_time.get());
+        }
+    }
+}
+
+pub struct GetTriggerOrderHistoryResponse(Bytes);
+
+response!(
+    GetTriggerOrderHistoryResponse,
+    Vec<TriggerOrder<'a>>,
+    Vec<TriggerOrderPartial<'a>>
+);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "deny-unknown-fields", serde(deny_unknown_fields))]
+pub struct TriggerOrder<'a> {
+    pub id: u64,
+    pub market: &'a str,
+    pub future: Option<&'a str>,
+    pub side: Side,
+    pub r#type: TriggerOrderType,
+    /// Type of the order placed once triggered.
+    pub order_type: OrderType,
+    /// Limit price of the order placed once triggered, `None` for market
+    /// orders.
+    pub order_price: Option<Decimal>,
+    pub trigger_price: Option<Decimal>,
+    pub trail_start: Option<Decimal>,
+    pub trail_value: Option<Decimal>,
+    pub size: Decimal,
+    pub filled_size: Decimal,
+    pub avg_fill_price: Option<Decimal>,
+    pub status: TriggerOrderStatus,
+    pub created_at: FtxDateTime,
+    pub triggered_at: Option<FtxDateTime>,
+    /// Id of the order placed once triggered.
+    pub order_id: Option<u64>,
+    pub error: Option<&'a str>,
+    pub cancel_reason: Option<&'a str>,
+    pub reduce_only: bool,
+    /// Whether the order is placed again if it's not filled, for market
+    /// orders.
+    pub retry_until_filled: bool,
+}
+
+impl<'a> TryFrom<TriggerOrderPartial<'a>> for TriggerOrder<'a> {
+    type Error = serde_json::Error;
+
+    fn try_from(val: TriggerOrderPartial<'a>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: val.id.deserialize()?,
+            market: val.market,
+            future: val.future,
+            side: val.side.deserialize()?,
+            r#type: val.r#type.deserialize()?,
+            order_type: val.order_type.deserialize()?,
+            order_price: val.order_price.deserialize()?,
+            trigger_price: val.trigger_price.deserialize()?,
+            trail_start: val.trail_start.deserialize()?,
+            trail_value: val.trail_value.deserialize()?,
+            size: val.size.deserialize()?,
+            filled_size: val.filled_size.deserialize()?,
+            avg_fill_price: val.avg_fill_price.deserialize()?,
+            status: val.status.deserialize()?,
+            created_at: val.created_at.deserialize()?,
+            triggered_at: val.triggered_at.deserialize()?,
+            order_id: val.order_id.deserialize()?,
+            error: val.error,
+            cancel_reason: val.cancel_reason,
+            reduce_only: val.reduce_only.deserialize()?,
+            retry_until_filled: val.retry_until_filled.deserialize()?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "deny-unknown-fields", serde(deny_unknown_fields))]
+pub struct TriggerOrderPartial<'a> {
+    #[serde(borrow)]
+    pub id: Json<'a, u64>,
+    pub market: &'a str,
+    pub future: Option<&'a str>,
+    #[serde(borrow)]
+    pub side: Json<'a, Side>,
+    #[serde(borrow)]
+    pub r#type: Json<'a, TriggerOrderType>,
+    #[serde(borrow)]
+    pub order_type: Json<'a, OrderType>,
+    #[serde(borrow)]
+    pub order_price: OptJson<'a, Decimal>,
+    #[serde(borrow)]
+    pub trigger_price: OptJson<'a, Decimal>,
+    #[serde(borrow)]
+    pub trail_start: OptJson<'a, Decimal>,
+    #[serde(borrow)]
+    pub trail_value: OptJson<'a, Decimal>,
+    #[serde(borrow)]
+    pub size: Json<'a, Decimal>,
+    #[serde(borrow)]
+    pub filled_size: Json<'a, Decimal>,
+    #[serde(borrow)]
+    pub avg_fill_price: OptJson<'a, Decimal>,
+    #[serde(borrow)]
+    pub status: Json<'a, TriggerOrderStatus>,
+    #[serde(borrow)]
+    pub created_at: Json<'a, FtxDateTime>,
+    #[serde(borrow)]
+    pub triggered_at: OptJson<'a, FtxDateTime>,
+    #[serde(borrow)]
+    pub order_id: OptJson<'a, u64>,
+    pub error: Option<&'a str>,
+    pub cancel_reason: Option<&'a str>,
+    #[serde(borrow)]
+    pub reduce_only: Json<'a, bool>,
+    #[serde(borrow)]
+    pub retry_until_filled: Json<'a, bool>,
+}
+
+/// A trigger order as acknowledged when placed or modified, before
+/// it's been processed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "deny-unknown-fields", serde(deny_unknown_fields))]
+pub struct TriggerOrderPlaced<'a> {
+    pub id: u64,
+    pub market: &'a str,
+    pub future: Option<&'a str>,
+    pub side: Side,
+    pub r#type: TriggerOrderType,
+    pub order_type: OrderType,
+    pub order_price: Option<Decimal>,
+    pub trigger_price: Option<Decimal>,
+    pub trail_start: Option<Decimal>,
+    pub trail_value: Option<Decimal>,
+    pub size: Decimal,
+    pub status: TriggerOrderStatus,
+    pub created_at: FtxDateTime,
+    pub triggered_at: Option<FtxDateTime>,
+    pub order_id: Option<u64>,
+    pub error: Option<&'a str>,
+    pub reduce_only: bool,
+    pub retry_until_filled: bool,
+}
+
+impl<'a> TryFrom<TriggerOrderPlacedPartial<'a>> for TriggerOrderPlaced<'a> {
+    type Error = serde_json::Error;
+
+    fn try_from(val: TriggerOrderPlacedPartial<'a>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: val.id.deserialize()?,
+            market: val.market,
+            future: val.future,
+            side: val.side.deserialize()?,
+            r#type: val.r#type.deserialize()?,
+            order_type: val.order_type.deserialize()?,
+            order_price: val.order_price.deserialize()?,
+            trigger_price: val.trigger_price.deserialize()?,
+            trail_start: val.trail_start.deserialize()?,
+            trail_value: val.trail_value.deserialize()?,
+            size: val.size.deserialize()?,
+            status: val.status.deserialize()?,
+            created_at: val.created_at.deserialize()?,
+            triggered_at: val.triggered_at.deserialize()?,
+            order_id: val.order_id.deserialize()?,
+            error: val.error,
+            reduce_only: val.reduce_only.deserialize()?,
+            retry_until_filled: val.retry_until_filled.deserialize()?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "deny-unknown-fields", serde(deny_unknown_fields))]
+pub struct TriggerOrderPlacedPartial<'a> {
+    #[serde(borrow)]
+    pub id: Json<'a, u64>,
+    pub market: &'a str,
+    pub future: Option<&'a str>,
+    #[serde(borrow)]
+    pub side: Json<'a, Side>,
+    #[serde(borrow)]
+    pub r#type: Json<'a, TriggerOrderType>,
+    #[serde(borrow)]
+    pub order_type: Json<'a, OrderType>,
+    #[serde(borrow, default)]
+    pub order_price: OptJson<'a, Decimal>,
+    #[serde(borrow, default)]
+    pub trigger_price: OptJson<'a, Decimal>,
+    #[serde(borrow, default)]
+    pub trail_start: OptJson<'a, Decimal>,
+    #[serde(borrow, default)]
+    pub trail_value: OptJson<'a, Decimal>,
+    #[serde(borrow)]
+    pub size: Json<'a, Decimal>,
+    #[serde(borrow)]
+    pub status: Json<'a, TriggerOrderStatus>,
+    #[serde(borrow)]
+    pub created_at: Json<'a, FtxDateTime>,
+    #[serde(borrow, default)]
+    pub triggered_at: OptJson<'a, FtxDateTime>,
+    #[serde(borrow, default)]
+    pub order_id: OptJson<'a, u64>,
+    pub error: Option<&'a str>,
+    #[serde(borrow)]
+    pub reduce_only: Json<'a, bool>,
+    #[serde(borrow)]
+    pub retry_until_filled: Json<'a, bool>,
+}
+
+/// An order placed when a trigger order was triggered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "deny-unknown-fields", serde(deny_unknown_fields))]
+pub struct TriggerOrderTrigger<'a> {
+    pub time: FtxDateTime,
+    pub order_size: Option<Decimal>,
+    pub filled_size: Option<Decimal>,
+    pub order_id: Option<u64>,
+    /// Why placing the order failed, if it did.
+    pub error: Option<&'a str>,
+}
+
+impl<'a> TryFrom<TriggerOrderTriggerPartial<'a>> for TriggerOrderTrigger<'a> {
+    type Error = serde_json::Error;
+
+    fn try_from(val: TriggerOrderTriggerPartial<'a>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            time: val.time.deserialize()?,
+            order_size: val.order_size.deserialize()?,
+            filled_size: val.filled_size.deserialize()?,
+            order_id: val.order_id.deserialize()?,
+            error: val.error,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "deny-unknown-fields", serde(deny_unknown_fields))]
+pub struct TriggerOrderTriggerPartial<'a> {
+    #[serde(borrow)]
+    pub time: Json<'a, FtxDateTime>,
+    #[serde(borrow)]
+    pub order_size: OptJson<'a, Decimal>,
+    #[serde(borrow)]
+    pub filled_size: OptJson<'a, Decimal>,
+    #[serde(borrow)]
+    pub order_id: OptJson<'a, u64>,
+    pub error: Option<&'a str>,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryInto;
+
+    use crate::Response;
+
+    use super::*;
+
+    #[test]
+    fn get_trigger_order_history() {
+        let json = r#"
+{
+  "success": true,
+  "result": [
+    {
+      "avgFillPrice": 9787.5,
+      "cancelReason": null,
+      "createdAt": "2020-01-03T04:45:41.223768+00:00",
+      "error": null,
+      "filledSize": 0.0001,
+      "future": "BTC-PERP",
+      "id": 8768,
+      "market": "BTC-PERP",
+      "orderId": 1234567,
+      "orderPrice": null,
+      "orderType": "market",
+      "reduceOnly": false,
+      "retryUntilFilled": true,
+      "side": "buy",
+      "size": 0.0001,
+      "status": "triggered",
+      "trailStart": null,
+      "trailValue": null,
+      "triggerPrice": 9787.5,
+      "triggeredAt": "2020-01-03T04:46:35.148086+00:00",
+      "type": "stop"
+    },
+    {
+      "avgFillPrice": null,
+      "cancelReason": null,
+      "createdAt": "2020-01-04T10:02:13.481305+00:00",
+      "error": null,
+      "filledSize": 0,
+      "future": "BTC-PERP",
+      "id": 8771,
+      "market": "BTC-PERP",
+      "orderId": null,
+      "orderPrice": 10500,
+      "orderType": "limit",
+      "reduceOnly": true,
+      "retryUntilFilled": false,
+      "side": "sell",
+      "size": 0.0001,
+      "status": "open",
+      "trailStart": null,
+      "trailValue": null,
+      "triggerPrice": 10400,
+      "triggeredAt": null,
+      "type": "take_profit"
+    }
+  ]
+}
+"#;
+        let response = GetTriggerOrderHistoryResponse(json.as_bytes().into());
+
+        let from_partial: Vec<TriggerOrder<'_>> = response
+            .deserialize_partial()
+            .unwrap()
+            .into_iter()
+            .map(|p| TriggerOrder::try_from(p).unwrap())
+            .collect();
+
+        let orders = response.deserialize().unwrap();
+        assert_eq!(orders, from_partial);
+
+        assert_eq!(orders[0].status, TriggerOrderStatus::Triggered);
+        assert_eq!(orders[0].r#type, TriggerOrderType::Stop);
+        assert_eq!(orders[0].order_type, OrderType::Market);
+        assert!(orders[0].triggered_at.is_some());
+        assert!(orders[0].retry_until_filled);
+
+        assert_eq!(orders[1].status, TriggerOrderStatus::Open);
+        assert_eq!(orders[1].r#type, TriggerOrderType::TakeProfit);
+        assert_eq!(orders[1].order_type, OrderType::Limit);
+        assert_eq!(orders[1].triggered_at, None);
+        assert_eq!(orders[1].order_id, None);
+
+        assert_eq!(
+            serde_json::from_str::<TriggerOrderStatus>(r#""cancelled""#).unwrap(),
+            TriggerOrderStatus::Cancelled
+        );
+    }
+
+    #[test]
+    fn get_open_trigger_orders() {
+        let json = r#"
+{
+  "success": true,
+  "result": [
+    {
+      "avgFillPrice": null,
+      "createdAt": "2019-03-05T09:56:55.728933+00:00",
+      "error": null,
+      "filledSize": 0,
+      "future": "XRP-PERP",
+      "id": 50001,
+      "market": "XRP-PERP",
+      "orderId": null,
+      "orderPrice": null,
+      "orderType": "market",
+      "reduceOnly": false,
+      "retryUntilFilled": false,
+      "side": "buy",
+      "size": 0.003,
+      "status": "open",
+      "trailStart": null,
+      "trailValue": null,
+      "triggerPrice": 0.49,
+      "triggeredAt": null,
+      "type": "stop"
+    }
+  ]
+}
+"#;
+        let response = GetOpenTriggerOrdersResponse(json.as_bytes().into());
+
+        let from_partial: Vec<TriggerOrder<'_>> = response
+            .deserialize_partial()
+            .unwrap()
+            .into_iter()
+            .map(|p| TriggerOrder::try_from(p).unwrap())
+            .collect();
+
+        let orders = response.deserialize().unwrap();
+        assert_eq!(orders, from_partial);
+
+        assert_eq!(orders[0].status, TriggerOrderStatus::Open);
+        assert_eq!(orders[0].trigger_price, Some(Decimal::new(49, 2)));
+        assert_eq!(orders[0].cancel_reason, None);
+
+        let request = GetOpenTriggerOrders {
+            market: Some("XRP-PERP"),
+            r#type: Some(TriggerOrderType::TrailingStop),
+        };
+
+        assert_eq!(
+            request.query_params(),
+            Some(vec![
+                ("market", "XRP-PERP".into()),
+                ("type", "trailing_stop".into())
+            ])
+        );
+    }
+
+    #[test]
+    fn get_trigger_order_triggers() {
+        let json = r#"
+{
+  "success": true,
+  "result": [
+    {
+      "error": null,
+      "filledSize": 4.0,
+      "orderSize": 4.0,
+      "orderId": 23456,
+      "time": "2020-01-19T09:23:36.570904+00:00"
+    }
+  ]
+}
+"#;
+        let response = GetTriggerOrderTriggersResponse(json.as_bytes().into());
+
+        let from_partial: Vec<TriggerOrderTrigger<'_>> = response
+            .deserialize_partial()
+            .unwrap()
+            .into_iter()
+            .map(|p| TriggerOrderTrigger::try_from(p).unwrap())
+            .collect();
+
+        let triggers = response.deserialize().unwrap();
+        assert_eq!(triggers, from_partial);
+        assert_eq!(triggers[0].order_id, Some(23456));
+
+        assert_eq!(
+            GetTriggerOrderTriggers { order_id: 50001 }.path(),
+            "/conditional_orders/50001/triggers"
+        );
+    }
+
+    const TRIGGER_ORDER_PLACED_JSON: &str = r#"
+{
+  "success": true,
+  "result": {
+    "createdAt": "2019-03-05T09:56:55.728933+00:00",
+    "future": "XRP-PERP",
+    "id": 9596912,
+    "market": "XRP-PERP",
+    "triggerPrice": 0.306525,
+    "orderId": null,
+    "side": "sell",
+    "size": 31431,
+    "status": "open",
+    "type": "stop",
+    "orderPrice": null,
+    "error": null,
+    "triggeredAt": null,
+    "reduceOnly": false,
+    "orderType": "market",
+    "retryUntilFilled": false
+  }
+}
+"#;
+
+    #[test]
+    fn place_trigger_order() {
+        let request = PlaceTriggerOrder {
+            market: "XRP-PERP",
+            side: Side::Sell,
+            size: "31431".parse().unwrap(),
+            r#type: TriggerOrderType::TrailingStop,
+            trigger_price: None,
+            order_price: None,
+            trail_value: Some("-0.05".parse().unwrap()),
+            reduce_only: Some(true),
+            retry_until_filled: None,
+        };
+
+        assert_eq!(
+            request.to_json().unwrap().unwrap(),
+            r#"{"market":"XRP-PERP","side":"sell","size":"31431","type":"trailingStop","trailValue":"-0.05","reduceOnly":true}"#
+        );
+        assert!(!request.is_idempotent());
+
+        let response = PlaceTriggerOrderResponse(TRIGGER_ORDER_PLACED_JSON.as_bytes().into());
+
+        let from_partial: TriggerOrderPlaced<'_> =
+            response.deserialize_partial().unwrap().try_into().unwrap();
+
+        let placed = response.deserialize().unwrap();
+        assert_eq!(placed, from_partial);
+        assert_eq!(placed.trail_value, None);
+        assert_eq!(placed.trigger_price, Some(Decimal::new(306525, 6)));
+    }
+
+    #[test]
+    fn modify_trigger_order() {
+        let request = ModifyTriggerOrder {
+            order_id: 9596912,
+            opts: ModifyTriggerOrderOpts {
+                trigger_price: Some("0.3".parse().unwrap()),
+                ..Default::default()
+            },
+        };
+
+        assert_eq!(request.path(), "/conditional_orders/9596912/modify");
+        assert_eq!(
+            request.to_json().unwrap().unwrap(),
+            r#"{"triggerPrice":"0.3"}"#
+        );
+
+        let response = ModifyTriggerOrderResponse(TRIGGER_ORDER_PLACED_JSON.as_bytes().into());
+        assert_eq!(response.deserialize().unwrap().id, 9596912);
+    }
+
+    #[test]
+    fn cancel_trigger_order() {
+        let request = CancelTriggerOrder { order_id: 9596912 };
+        assert_eq!(request.path(), "/conditional_orders/9596912");
+
+        let response = CancelTriggerOrderResponse(
+            r#"{"success": true, "result": "Order queued for cancellation"}"#
+                .as_bytes()
+                .into(),
+        );
+        response.deserialize().unwrap();
+    }
+}
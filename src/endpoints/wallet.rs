@@ -1,14 +1,25 @@
-use std::{collections::HashMap, convert::TryFrom};
+use std::{borrow::Cow, collections::HashMap, convert::TryFrom, fmt};
 
 use bytes::Bytes;
-use reqwest::Method;
+use http::Method;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
-use crate::{data::AccountName, private::Sealed, Json, OptJson, Request};
+use crate::{
+    data::{AccountName, FtxDateTime, PositiveDecimal},
+    error::{Error, ErrorKind},
+    private::Sealed,
+    Json, OptJson, QueryParams, Request, Response,
+};
 
 use super::macros::response;
 
+macro_rules! get_deposit_address_path {
+    () => {
+        "/wallet/deposit_address/{coin}"
+    };
+}
+
 /// Retrieve info on all coins.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct GetCoins;
@@ -27,6 +38,22 @@ pub struct GetCoinsResponse(Bytes);
 
 response!(GetCoinsResponse, Vec<Coin<'a>>, Vec<CoinPartial<'a>>);
 
+impl GetCoinsResponse {
+    /// All coins except those marked as hidden, e.g. because they've
+    /// been delisted.
+    pub fn visible(&self) -> Result<Vec<Coin<'_>>, Error> {
+        let mut coins = self.deserialize()?;
+        coins.retain(|c| !c.hidden);
+        Ok(coins)
+    }
+}
+
+/// Coins which count towards collateral, each contributing its value
+/// scaled by its `collateral_weight`.
+pub fn collateral_coins<'a, 'b>(coins: &'b [Coin<'a>]) -> Vec<&'b Coin<'a>> {
+    coins.iter().filter(|c| c.collateral).collect()
+}
+
 /// Retrieve coin balances.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct GetBalances;
@@ -49,6 +76,19 @@ response!(
     Vec<BalancePartial<'a>>
 );
 
+impl GetBalancesResponse {
+    /// The USD balance, or `None` if there isn't one. Only the matching
+    /// balance is fully deserialized.
+    pub fn usd_balance(&self) -> Result<Option<Balance<'_>>, Error> {
+        self.deserialize_partial()?
+            .into_iter()
+            .find(|b| b.coin == "USD")
+            .map(Balance::try_from)
+            .transpose()
+            .map_err(|e| Error::new(ErrorKind::DeserializationFailed).with_source(e))
+    }
+}
+
 /// Retrieve coin balances for all account.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct GetAllBalances;
@@ -71,6 +111,194 @@ response!(
     HashMap<AccountName<'a>, Vec<BalancePartial<'a>>>
 );
 
+/// Retrieve the address to deposit a coin to. `method` picks the
+/// network, see [`DepositMethod::as_param`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GetDepositAddress<'a> {
+    pub coin: &'a str,
+    pub method: Option<&'a str>,
+}
+
+impl<'a> Sealed for GetDepositAddress<'a> {}
+
+impl<'a> Request<true> for GetDepositAddress<'a> {
+    const PATH: &'static str = get_deposit_address_path!();
+
+    const METHOD: Method = Method::GET;
+
+    type Response = GetDepositAddressResponse;
+
+    fn path(&self) -> Cow<'_, str> {
+        Cow::Owned(format!(get_deposit_address_path!(), coin = self.coin))
+    }
+
+    fn query_params(&self) -> Option<QueryParams> {
+        self.method.map(|method| vec![("method", method.into())])
+    }
+}
+
+pub struct GetDepositAddressResponse(Bytes);
+
+response!(
+    GetDepositAddressResponse,
+    DepositAddress<'a>,
+    DepositAddressPartial<'a>
+);
+
+/// Withdraw coins to an external address. `method` picks the network,
+/// see [`DepositMethod::as_param`], `password` is the withdrawal
+/// password and `code` the 2FA code, both required if enabled for the
+/// account.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub struct RequestWithdrawal<'a> {
+    pub coin: &'a str,
+    pub size: PositiveDecimal,
+    pub address: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub method: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<&'a str>,
+}
+
+// Never print the password or 2FA code.
+impl<'a> fmt::Debug for RequestWithdrawal<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RequestWithdrawal")
+            .field("coin", &self.coin)
+            .field("size", &self.size)
+            .field("address", &self.address)
+            .field("tag", &self.tag)
+            .field("method", &self.method)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a> Sealed for RequestWithdrawal<'a> {}
+
+impl<'a> Request<true> for RequestWithdrawal<'a> {
+    const PATH: &'static str = "/wallet/withdrawals";
+
+    const METHOD: Method = Method::POST;
+
+    type Response = RequestWithdrawalResponse;
+
+    fn to_json(&self) -> Option<Result<String, serde_json::Error>> {
+        Some(serde_json::to_string(self))
+    }
+
+    /// Sending it twice would withdraw twice, so it's never retried.
+    fn is_idempotent(&self) -> bool {
+        false
+    }
+}
+
+pub struct RequestWithdrawalResponse(Bytes);
+
+response!(
+    RequestWithdrawalResponse,
+    Withdrawal<'a>,
+    WithdrawalPartial<'a>
+);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "deny-unknown-fields", serde(deny_unknown_fields))]
+pub struct DepositAddress<'a> {
+    pub address: &'a str,
+    pub tag: Option<&'a str>,
+}
+
+impl<'a> TryFrom<DepositAddressPartial<'a>> for DepositAddress<'a> {
+    type Error = serde_json::Error;
+
+    fn try_from(val: DepositAddressPartial<'a>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            address: val.address,
+            tag: val.tag,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "deny-unknown-fields", serde(deny_unknown_fields))]
+pub struct DepositAddressPartial<'a> {
+    pub address: &'a str,
+    pub tag: Option<&'a str>,
+}
+
+/// Status of a withdrawal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WithdrawalStatus {
+    #[serde(rename = "requested")]
+    Requested,
+    #[serde(rename = "processing")]
+    Processing,
+    #[serde(rename = "sent")]
+    Sent,
+    #[serde(rename = "complete")]
+    Complete,
+    #[serde(rename = "cancelled")]
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "deny-unknown-fields", serde(deny_unknown_fields))]
+pub struct Withdrawal<'a> {
+    pub id: u64,
+    pub coin: &'a str,
+    pub size: Decimal,
+    pub address: &'a str,
+    pub tag: Option<&'a str>,
+    pub fee: Decimal,
+    pub status: WithdrawalStatus,
+    pub time: FtxDateTime,
+    pub txid: Option<&'a str>,
+}
+
+impl<'a> TryFrom<WithdrawalPartial<'a>> for Withdrawal<'a> {
+    type Error = serde_json::Error;
+
+    fn try_from(val: WithdrawalPartial<'a>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: val.id.deserialize()?,
+            coin: val.coin,
+            size: val.size.deserialize()?,
+            address: val.address,
+            tag: val.tag,
+            fee: val.fee.deserialize()?,
+            status: val.status.deserialize()?,
+            time: val.time.deserialize()?,
+            txid: val.txid,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "deny-unknown-fields", serde(deny_unknown_fields))]
+pub struct WithdrawalPartial<'a> {
+    #[serde(borrow)]
+    pub id: Json<'a, u64>,
+    pub coin: &'a str,
+    #[serde(borrow)]
+    pub size: Json<'a, Decimal>,
+    pub address: &'a str,
+    pub tag: Option<&'a str>,
+    #[serde(borrow)]
+    pub fee: Json<'a, Decimal>,
+    #[serde(borrow)]
+    pub status: Json<'a, WithdrawalStatus>,
+    #[serde(borrow)]
+    pub time: Json<'a, FtxDateTime>,
+    pub txid: Option<&'a str>,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "deny-unknown-fields", serde(deny_unknown_fields))]
@@ -107,6 +335,76 @@ pub struct Coin<'a> {
     pub index_price: f64,
 }
 
+impl<'a> Coin<'a> {
+    /// The network to deposit this coin on, picked from `methods`. A
+    /// method named after the coin itself, e.g. `btc` for BTC, is its
+    /// own chain and preferred. Otherwise the first available of ERC20,
+    /// TRC20, SPL, BEP20, BEP2 and Omni is chosen, then any other
+    /// method, e.g. `matic`.
+    pub fn primary_network(&self) -> Option<DepositMethod<'a>> {
+        const PREFERENCE: [DepositMethod<'static>; 6] = [
+            DepositMethod::Erc20,
+            DepositMethod::Trc20,
+            DepositMethod::Spl,
+            DepositMethod::Bep20,
+            DepositMethod::Bep2,
+            DepositMethod::Omni,
+        ];
+
+        let methods: Vec<DepositMethod<'a>> =
+            self.methods.iter().copied().map(Into::into).collect();
+
+        methods
+            .iter()
+            .find(|m| m.as_param().eq_ignore_ascii_case(self.id))
+            .or_else(|| PREFERENCE.iter().find(|p| methods.contains(p)))
+            .or_else(|| methods.first())
+            .copied()
+    }
+}
+
+/// A network coins can be deposited and withdrawn on, as listed in
+/// [`Coin::methods`]. Networks not known to this crate are kept as
+/// [`DepositMethod::Other`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DepositMethod<'a> {
+    Erc20,
+    Trc20,
+    Spl,
+    Bep20,
+    Bep2,
+    Omni,
+    Other(&'a str),
+}
+
+impl<'a> DepositMethod<'a> {
+    pub fn as_param(&self) -> &'a str {
+        match self {
+            Self::Erc20 => "erc20",
+            Self::Trc20 => "trx",
+            Self::Spl => "sol",
+            Self::Bep20 => "bsc",
+            Self::Bep2 => "bep2",
+            Self::Omni => "omni",
+            Self::Other(method) => method,
+        }
+    }
+}
+
+impl<'a> From<&'a str> for DepositMethod<'a> {
+    fn from(method: &'a str) -> Self {
+        match method {
+            "erc20" => Self::Erc20,
+            "trx" => Self::Trc20,
+            "sol" => Self::Spl,
+            "bsc" => Self::Bep20,
+            "bep2" => Self::Bep2,
+            "omni" => Self::Omni,
+            _ => Self::Other(method),
+        }
+    }
+}
+
 impl<'a> TryFrom<CoinPartial<'a>> for Coin<'a> {
     type Error = serde_json::Error;
 
@@ -258,9 +556,7 @@ mod tests {
 
     use super::*;
 
-    #[test]
-    fn get_coins() {
-        let json = r#"
+    const GET_COINS_JSON: &str = r#"
 {
   "success": true,
   "result": [
@@ -293,10 +589,44 @@ mod tests {
       "usdFungible": true,
       "imfWeight": 1.0,
       "mmfWeight": 1.0
+    },
+    {
+      "bep2Asset": null,
+      "canConvert": true,
+      "canDeposit": false,
+      "canWithdraw": false,
+      "collateral": true,
+      "collateralWeight": 1,
+      "initialCollateralWeight": 1,
+      "creditTo": null,
+      "erc20Contract": null,
+      "fiat": false,
+      "hasTag": false,
+      "hidden": true,
+      "id": "SRN",
+      "imageUrl": null,
+      "indexPrice": 1,
+      "isEtf": false,
+      "isToken": false,
+      "methods": [],
+      "name": "Sirin Labs Token",
+      "nftQuoteCurrencyEligible": true,
+      "splMint": null,
+      "spotMargin": true,
+      "spotMarginImf": 0.1,
+      "spotMarginImfFactor": 0.0,
+      "trc20Contract": null,
+      "usdFungible": true,
+      "imfWeight": 1.0,
+      "mmfWeight": 1.0
     }
   ]
 }
 "#;
+
+    #[test]
+    fn get_coins() {
+        let json = GET_COINS_JSON;
         let response = GetCoinsResponse(json.as_bytes().into());
 
         let from_partial: Vec<Coin<'_>> = response
@@ -307,6 +637,63 @@ mod tests {
             .collect();
 
         assert_eq!(response.deserialize().unwrap(), from_partial);
+
+        let visible = response.visible().unwrap();
+        assert_eq!(visible.len(), 1);
+        assert_eq!(visible[0].id, "USD");
+    }
+
+    #[test]
+    fn primary_network() {
+        let response = GetCoinsResponse(GET_COINS_JSON.as_bytes().into());
+        let coin = response.deserialize().unwrap().remove(0);
+
+        let usdt = Coin {
+            id: "USDT",
+            methods: vec!["omni", "sol", "erc20", "trx", "bsc"],
+            ..coin.clone()
+        };
+        assert_eq!(usdt.primary_network(), Some(DepositMethod::Erc20));
+
+        let btc = Coin {
+            id: "BTC",
+            methods: vec!["bsc", "btc", "sol"],
+            ..coin.clone()
+        };
+        assert_eq!(btc.primary_network(), Some(DepositMethod::Other("btc")));
+        assert_eq!(btc.primary_network().unwrap().as_param(), "btc");
+
+        // Token networks the crate doesn't know aren't the coin's chain.
+        let usdt = Coin {
+            id: "USDT",
+            methods: vec!["matic", "avax", "erc20", "hec"],
+            ..coin.clone()
+        };
+        assert_eq!(usdt.primary_network(), Some(DepositMethod::Erc20));
+
+        let usdt = Coin {
+            id: "USDT",
+            methods: vec!["matic", "avax"],
+            ..coin.clone()
+        };
+        assert_eq!(usdt.primary_network(), Some(DepositMethod::Other("matic")));
+
+        // Fiat can't be deposited on chain.
+        assert_eq!(coin.primary_network(), None);
+    }
+
+    #[test]
+    fn collateral() {
+        let response = GetCoinsResponse(GET_COINS_JSON.as_bytes().into());
+        let mut coins = response.deserialize().unwrap();
+
+        coins[1].collateral = false;
+        coins[1].collateral_weight = Decimal::ZERO;
+
+        let collateral = collateral_coins(&coins);
+        assert_eq!(collateral.len(), 1);
+        assert_eq!(collateral[0].id, "USD");
+        assert_eq!(collateral[0].collateral_weight, Decimal::ONE);
     }
 
     #[test]
@@ -323,6 +710,15 @@ mod tests {
       "usdValue": 2340.2,
       "availableWithoutBorrow": 2320.2,
       "availableForWithdrawal": 2320.2
+    },
+    {
+      "coin": "USD",
+      "free": 105.5,
+      "spotBorrow": 0.0,
+      "total": 110.5,
+      "usdValue": 110.5,
+      "availableWithoutBorrow": 105.5,
+      "availableForWithdrawal": 105.5
     }
   ]
 }
@@ -337,6 +733,7 @@ mod tests {
             .collect();
 
         assert_eq!(response.deserialize().unwrap(), from_partial);
+        assert_eq!(response.usd_balance().unwrap(), Some(from_partial[1]));
     }
 
     #[test]
@@ -398,4 +795,97 @@ mod tests {
 
         assert_eq!(response.deserialize().unwrap(), from_partial);
     }
+
+    #[test]
+    fn get_deposit_address() {
+        let request = GetDepositAddress {
+            coin: "USDT",
+            method: Some(DepositMethod::Erc20.as_param()),
+        };
+
+        assert_eq!(request.path(), "/wallet/deposit_address/USDT");
+        assert_eq!(
+            request.query_params(),
+            Some(vec![("method", "erc20".to_string())])
+        );
+        assert_eq!(
+            GetDepositAddress {
+                coin: "USDT",
+                method: None
+            }
+            .query_params(),
+            None
+        );
+
+        let json = r#"
+{
+  "success": true,
+  "result": {
+    "address": "0x83a127952d266A6eA306c40Ac62A4a70668FE3BE",
+    "tag": null
+  }
+}
+"#;
+        let response = GetDepositAddressResponse(json.as_bytes().into());
+
+        let from_partial: DepositAddress<'_> =
+            response.deserialize_partial().unwrap().try_into().unwrap();
+
+        let address = response.deserialize().unwrap();
+        assert_eq!(address, from_partial);
+        assert_eq!(
+            address.address,
+            "0x83a127952d266A6eA306c40Ac62A4a70668FE3BE"
+        );
+        assert_eq!(address.tag, None);
+    }
+
+    #[test]
+    fn request_withdrawal() {
+        let request = RequestWithdrawal {
+            coin: "USDTBEAR",
+            size: "20.2".parse().unwrap(),
+            address: "0x83a127952d266A6eA306c40Ac62A4a70668FE3BE",
+            tag: None,
+            method: Some("erc20"),
+            password: Some("hunter2"),
+            code: Some("152823"),
+        };
+
+        assert_eq!(
+            request.to_json().unwrap().unwrap(),
+            r#"{"coin":"USDTBEAR","size":"20.2","address":"0x83a127952d266A6eA306c40Ac62A4a70668FE3BE","method":"erc20","password":"hunter2","code":"152823"}"#
+        );
+        assert!(!request.is_idempotent());
+
+        let debug = format!("{:?}", request);
+        assert!(!debug.contains("hunter2"));
+        assert!(!debug.contains("152823"));
+
+        let json = r#"
+{
+  "success": true,
+  "result": {
+    "coin": "USDTBEAR",
+    "address": "0x83a127952d266A6eA306c40Ac62A4a70668FE3BE",
+    "tag": null,
+    "fee": 0,
+    "id": 1,
+    "size": "20.2",
+    "status": "requested",
+    "time": "2019-03-05T09:56:55.728933+00:00",
+    "txid": null
+  }
+}
+"#;
+        let response = RequestWithdrawalResponse(json.as_bytes().into());
+
+        let from_partial: Withdrawal<'_> =
+            response.deserialize_partial().unwrap().try_into().unwrap();
+
+        let withdrawal = response.deserialize().unwrap();
+        assert_eq!(withdrawal, from_partial);
+        assert_eq!(withdrawal.status, WithdrawalStatus::Requested);
+        assert_eq!(withdrawal.size, "20.2".parse().unwrap());
+    }
 }
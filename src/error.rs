@@ -1,6 +1,8 @@
 use hmac::digest::InvalidLength;
-use reqwest::StatusCode;
-use std::{error::Error as StdError, fmt};
+use http::StatusCode;
+use std::{error::Error as StdError, fmt, time::Duration};
+
+use crate::Request;
 
 pub(crate) type BoxError = Box<dyn StdError + Send + Sync>;
 
@@ -8,7 +10,12 @@ pub struct Error(Box<Inner>);
 
 impl Error {
     pub(crate) fn new(kind: ErrorKind) -> Self {
-        Error(Box::new(Inner { kind, source: None }))
+        Error(Box::new(Inner {
+            kind,
+            source: None,
+            retry_after: None,
+            exchange_message: None,
+        }))
     }
 
     pub(crate) fn with_source(mut self, source: impl Into<BoxError>) -> Self {
@@ -16,6 +23,81 @@ impl Error {
         self
     }
 
+    /// The error message the exchange rejected the request with. Also
+    /// kept as the source so it shows up in error chains.
+    pub(crate) fn with_exchange_message(mut self, message: &str) -> Self {
+        self.0.exchange_message = Some(message.to_owned());
+        self.with_source(message.to_owned())
+    }
+
+    /// How long the exchange asked for the client to wait before retrying
+    /// a request rejected for exceeding rate limits.
+    #[cfg_attr(not(feature = "client"), allow(dead_code))]
+    pub(crate) fn with_retry_after(mut self, retry_after: Option<Duration>) -> Self {
+        self.0.retry_after = retry_after;
+        self
+    }
+
+    #[cfg_attr(not(feature = "client"), allow(dead_code))]
+    pub(crate) fn kind(&self) -> ErrorKind {
+        self.0.kind
+    }
+
+    /// How long the exchange asked for the client to wait before
+    /// retrying, for requests rejected for exceeding rate limits. Taken
+    /// from the response's `Retry-After` header if it had one.
+    pub fn retry_after(&self) -> Option<Duration> {
+        self.0.retry_after
+    }
+
+    /// The error message sent by the exchange, e.g. `Order already
+    /// closed`, if it rejected the request or asked for it to be
+    /// retried.
+    pub fn exchange_message(&self) -> Option<&str> {
+        self.0.exchange_message.as_deref()
+    }
+
+    /// The reason given by the exchange if the request was rejected.
+    pub fn rejection_reason(&self) -> Option<RejectionReason> {
+        if self.0.kind != ErrorKind::RejectedByExchange {
+            return None;
+        }
+
+        Some(
+            self.exchange_message()
+                .map_or(RejectionReason::Other, RejectionReason::from_message),
+        )
+    }
+
+    /// Whether the request may succeed if sent again because rate limits
+    /// were hit or the exchange asked for it to be retried. In both cases
+    /// the request wasn't processed, so it's safe to resend whatever it
+    /// was. Timeouts and server errors aren't included as the request may
+    /// already have been executed.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self.0.kind,
+            ErrorKind::RetryRequested | ErrorKind::RateLimitExceeded
+        )
+    }
+
+    /// Whether `request` may succeed if sent again. On top of
+    /// [`is_retryable`](Self::is_retryable) errors, idempotent requests,
+    /// see [`Request::is_idempotent`], can also be retried after a
+    /// timeout, dropped connection or server error.
+    pub fn is_retryable_for<R, const AUTH: bool>(&self, request: &R) -> bool
+    where
+        R: Request<AUTH>,
+    {
+        let transient = match self.0.kind {
+            ErrorKind::RequestExecutionFailed(code) => code.map_or(true, |c| c.is_server_error()),
+            _ => false,
+        };
+
+        self.is_retryable() || (transient && request.is_idempotent())
+    }
+
+    #[cfg_attr(not(feature = "client"), allow(dead_code))]
     pub(crate) fn from_status_code(code: Option<StatusCode>) -> Self {
         if code.map_or(false, |c| c == 429) {
             Error::new(ErrorKind::RateLimitExceeded)
@@ -30,6 +112,8 @@ impl fmt::Debug for Error {
         f.debug_struct("Error")
             .field("kind", &self.0.kind)
             .field("source", &self.0.source)
+            .field("retry_after", &self.0.retry_after)
+            .field("exchange_message", &self.0.exchange_message)
             .finish()
     }
 }
@@ -55,6 +139,8 @@ impl From<InvalidLength> for Error {
 struct Inner {
     kind: ErrorKind,
     source: Option<BoxError>,
+    retry_after: Option<Duration>,
+    exchange_message: Option<String>,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -65,10 +151,27 @@ pub enum ErrorKind {
     InvalidUrl,
     InvalidPayload,
     RequestBuildFailed,
+    /// No response was received, e.g. after a timeout, or the exchange
+    /// responded with a server error status. In the latter case any error
+    /// message it sent is available from [`Error::exchange_message`].
     RequestExecutionFailed(Option<StatusCode>),
+    /// The exchange responded with `429 Too Many Requests`. Any error
+    /// message it sent is available from [`Error::exchange_message`].
     RateLimitExceeded,
+    /// The response body was larger than the client's configured limit.
+    ResponseTooLarge,
     DeserializationFailed,
     RejectedByExchange,
+    /// The exchange failed to process the request and asked for it to be
+    /// retried.
+    RetryRequested,
+    /// An operation spanning several requests didn't complete in time.
+    Timeout,
+    /// A balance checked before sending a request was too low for it.
+    InsufficientBalance,
+    /// The request was abandoned by the caller before it completed.
+    Cancelled,
+    WriteFailed,
 }
 
 impl fmt::Display for ErrorKind {
@@ -86,8 +189,45 @@ impl fmt::Display for ErrorKind {
                 None => f.write_str("request failed"),
             },
             RateLimitExceeded => f.write_str("rate limits exceeded"),
+            ResponseTooLarge => f.write_str("response body too large"),
             DeserializationFailed => f.write_str("failed to deserialize response"),
             RejectedByExchange => f.write_str("request rejected by the exchange"),
+            RetryRequested => f.write_str("exchange asked for the request to be retried"),
+            Timeout => f.write_str("timed out"),
+            InsufficientBalance => f.write_str("insufficient balance"),
+            Cancelled => f.write_str("request cancelled"),
+            WriteFailed => f.write_str("failed to write response data"),
+        }
+    }
+}
+
+/// Known reasons for the exchange rejecting a request, parsed from the
+/// error message it returns.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RejectionReason {
+    /// A post only order would have crossed the book and been taken.
+    PostOnlyWouldTake,
+    /// The requested future doesn't exist, e.g. because it has been
+    /// delisted.
+    UnknownFuture,
+    /// The order is smaller than the market's minimum size.
+    SizeTooSmall,
+    Other,
+}
+
+impl RejectionReason {
+    fn from_message(msg: &str) -> Self {
+        let msg = msg.to_ascii_lowercase();
+
+        if msg == "post only order would be taken" {
+            Self::PostOnlyWouldTake
+        } else if msg.starts_with("no such future") {
+            Self::UnknownFuture
+        } else if msg.starts_with("size too small") {
+            Self::SizeTooSmall
+        } else {
+            Self::Other
         }
     }
 }
@@ -1,8 +1,12 @@
 pub mod error;
-use error::Error;
+use error::{Error, ErrorKind};
 
+#[cfg(feature = "client")]
 mod client;
-pub use client::{AuthClient, Client};
+#[cfg(feature = "client")]
+pub use client::{
+    AuthClient, Client, Clock, FixedClock, RequestObserver, RetryPolicy, SystemClock,
+};
 
 pub mod data;
 
@@ -13,13 +17,40 @@ pub mod auth;
 
 use async_trait::async_trait;
 use bytes::Bytes;
-use reqwest::Method;
-use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use http::Method;
+use serde::{de::IgnoredAny, Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::value::RawValue;
-use std::{borrow::Cow, convert::TryFrom, marker::PhantomData, time::Duration};
+use std::{
+    borrow::Cow, collections::HashMap, convert::TryFrom, io, marker::PhantomData, time::Duration,
+};
 
 pub type QueryParams = Vec<(&'static str, String)>;
 
+/// URL encodes query parameters directly into a buffer. Produces the
+/// same output as encoding the equivalent `QueryParams` with
+/// `serde_urlencoded`.
+pub struct QueryWriter<'a>(form_urlencoded::Serializer<'a, &'a mut String>);
+
+impl<'a> QueryWriter<'a> {
+    /// Append parameters to `buf`, starting after its current
+    /// contents.
+    pub fn new(buf: &'a mut String) -> Self {
+        let start = buf.len();
+        Self(form_urlencoded::Serializer::for_suffix(buf, start))
+    }
+
+    pub fn append(&mut self, key: &str, val: &str) -> &mut Self {
+        self.0.append_pair(key, val);
+        self
+    }
+
+    pub fn append_uint(&mut self, key: &str, val: impl Into<u128>) -> &mut Self {
+        let mut buf = itoa::Buffer::new();
+        self.0.append_pair(key, buf.format(val.into()));
+        self
+    }
+}
+
 #[async_trait]
 pub trait Executor<R>
 where
@@ -36,6 +67,15 @@ where
     async fn execute(&self, request: &R, timeout: Option<Duration>) -> Result<R::Response, Error>;
 }
 
+/// The rate limit bucket a request counts against. Order placement,
+/// editing and cancellation is limited separately from, and more
+/// tightly than, other requests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RateLimitClass {
+    Orders,
+    NonOrders,
+}
+
 pub trait Request<const AUTH: bool>: private::Sealed {
     const PATH: &'static str;
 
@@ -43,6 +83,10 @@ pub trait Request<const AUTH: bool>: private::Sealed {
 
     type Response: Response;
 
+    fn method(&self) -> Method {
+        Self::METHOD
+    }
+
     fn path(&self) -> Cow<'_, str> {
         Cow::Borrowed(Self::PATH)
     }
@@ -51,9 +95,46 @@ pub trait Request<const AUTH: bool>: private::Sealed {
         None
     }
 
+    /// Write the query parameters straight into the URL being
+    /// built. Defaults to writing the pairs from `query_params`,
+    /// endpoints with many numeric parameters override this to skip
+    /// allocating a `String` per value.
+    fn write_query(&self, query: &mut QueryWriter<'_>) {
+        if let Some(params) = self.query_params() {
+            for (key, val) in params.iter() {
+                query.append(key, val);
+            }
+        }
+    }
+
     fn to_json(&self) -> Option<Result<String, serde_json::Error>> {
         None
     }
+
+    /// Defaults to [`RateLimitClass::Orders`] for `POST` and `DELETE`
+    /// requests under `/orders` and `/conditional_orders`.
+    fn rate_limit_class(&self) -> RateLimitClass {
+        let method = self.method();
+        let path = self.path();
+
+        if (method == Method::POST || method == Method::DELETE)
+            && (path.starts_with("/orders") || path.starts_with("/conditional_orders"))
+        {
+            RateLimitClass::Orders
+        } else {
+            RateLimitClass::NonOrders
+        }
+    }
+
+    /// Whether sending the request more than once has the same effect as
+    /// sending it once, so it's safe to retry after a failure where it's
+    /// unknown if the exchange processed it. Defaults to true for `GET`
+    /// and `DELETE` requests.
+    fn is_idempotent(&self) -> bool {
+        let method = self.method();
+
+        method == Method::GET || method == Method::DELETE
+    }
 }
 
 pub trait Response: From<Bytes> + AsRef<Bytes> + private::Sealed {
@@ -78,6 +159,112 @@ pub trait Response: From<Bytes> + AsRef<Bytes> + private::Sealed {
     {
         FtxResponse::try_from(self.as_ref().as_ref())?.deserialize()
     }
+
+    /// Write each element of a list response to `w` as a line of
+    /// JSON.
+    fn write_ndjson<'a: 'de, 'de, W>(&'a self, w: &mut W) -> Result<(), Error>
+    where
+        W: io::Write,
+        <Self as Response>::Data<'a>: Deserialize<'de> + IntoIterator,
+        <<Self as Response>::Data<'a> as IntoIterator>::Item: Serialize,
+    {
+        for item in self.deserialize()? {
+            serde_json::to_writer(&mut *w, &item)
+                .map_err(|e| Error::new(ErrorKind::WriteFailed).with_source(e))?;
+            w.write_all(b"\n")
+                .map_err(|e| Error::new(ErrorKind::WriteFailed).with_source(e))?;
+        }
+
+        Ok(())
+    }
+
+    /// Whether a list response returned no elements, e.g. because
+    /// there was no data in the requested range. Only the envelope is
+    /// parsed, none of the elements are deserialized.
+    fn is_empty<'a>(&'a self) -> Result<bool, Error>
+    where
+        <Self as Response>::Data<'a>: IntoIterator,
+    {
+        FtxResponse::<IgnoredAny>::try_from(self.as_ref().as_ref())?.is_empty_array()
+    }
+
+    /// Fail early if the exchange rejected the request, without
+    /// deserializing the result. Any error is the same one `deserialize`
+    /// would return for a rejection.
+    fn into_result(self) -> Result<Self, Error> {
+        FtxResponse::<IgnoredAny>::try_from(self.as_ref().as_ref())?.ensure_success()?;
+
+        Ok(self)
+    }
+
+    /// The raw value at a JSON pointer into the body, e.g.
+    /// `/result/0/price`, for lazily pulling out fields which aren't
+    /// modelled. Returns `None` if nothing is at that location. Only the
+    /// objects and arrays along the path are parsed.
+    fn get_path(&self, pointer: &str) -> Result<Option<&RawValue>, Error> {
+        let body: &RawValue = serde_json::from_slice(self.as_ref().as_ref())
+            .map_err(|e| Error::new(ErrorKind::DeserializationFailed).with_source(e))?;
+
+        lookup_pointer(body, pointer)
+            .map_err(|e| Error::new(ErrorKind::DeserializationFailed).with_source(e))
+    }
+
+    /// The `error` message returned by the exchange, or `None` if the
+    /// request succeeded or the body couldn't be parsed. Only the
+    /// envelope is parsed, the result is left untouched.
+    fn error_message(&self) -> Option<&str> {
+        FtxResponse::<IgnoredAny>::try_from(self.as_ref().as_ref())
+            .ok()?
+            .error()
+    }
+}
+
+/// Follow a JSON pointer, as defined by RFC 6901, into `value`.
+fn lookup_pointer<'a>(
+    mut value: &'a RawValue,
+    pointer: &str,
+) -> serde_json::Result<Option<&'a RawValue>> {
+    if pointer.is_empty() {
+        return Ok(Some(value));
+    }
+
+    let tokens = match pointer.strip_prefix('/') {
+        Some(tokens) => tokens.split('/'),
+        None => return Ok(None),
+    };
+
+    for token in tokens {
+        let token = token.replace("~1", "/").replace("~0", "~");
+
+        let next = match value.get().trim_start().as_bytes().first() {
+            Some(b'{') => {
+                let mut fields: HashMap<String, &RawValue> = serde_json::from_str(value.get())?;
+                fields.remove(&token)
+            }
+            Some(b'[') => {
+                let mut elements: Vec<&RawValue> = serde_json::from_str(value.get())?;
+
+                // Leading zeros and signs aren't valid array indices.
+                let index = match token.as_bytes() {
+                    [b'0'] => Some(0),
+                    [b'1'..=b'9', ..] => token.parse().ok(),
+                    _ => None,
+                };
+
+                index
+                    .filter(|&i| i < elements.len())
+                    .map(|i| elements.swap_remove(i))
+            }
+            _ => None,
+        };
+
+        match next {
+            Some(next) => value = next,
+            None => return Ok(None),
+        }
+    }
+
+    Ok(Some(value))
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -134,6 +321,13 @@ where
     }
 }
 
+// Derived `Default` would require `T: Default`.
+impl<'a, T> Default for OptJson<'a, T> {
+    fn default() -> Self {
+        Self(None)
+    }
+}
+
 impl<'a, T> From<Option<Json<'a, T>>> for OptJson<'a, T> {
     fn from(value: Option<Json<'a, T>>) -> Self {
         Self(value)
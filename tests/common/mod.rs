@@ -24,7 +24,7 @@ static THROTTLER: Lazy<Receiver<Sender<()>>> = Lazy::new(|| {
     rx
 });
 
-pub async fn make_request<'de, R>(request: &R) -> R::Response
+pub async fn make_request<R>(request: &R) -> R::Response
 where
     R: Request<false> + Send + Sync,
 {
@@ -44,7 +44,7 @@ where
     response
 }
 
-pub async fn make_auth_request<'de, R, E>(client: &E, request: &R) -> R::Response
+pub async fn make_auth_request<R, E>(client: &E, request: &R) -> R::Response
 where
     R: Request<true> + Send + Sync,
     E: AuthExecutor<R>,
@@ -78,12 +78,12 @@ impl TestConfig {
     fn new() -> Result<Self, config::ConfigError> {
         dotenv().ok();
 
-        Ok(Config::builder()
+        Config::builder()
             .set_default("perform_auth_api_tests", false)?
             .set_default("perform_order_placement_tests", false)?
             .add_source(Environment::with_prefix("FTX"))
             .build()?
-            .try_deserialize()?)
+            .try_deserialize()
     }
 }
 
@@ -97,10 +97,10 @@ impl AuthTestClient {
             CONFIG.main_public_key.as_ref(),
         ) {
             (Some(private_key), Some(public_key)) => {
-                if private_key.len() == 0 {
+                if private_key.is_empty() {
                     panic!("empty private key")
                 }
-                if public_key.len() == 0 {
+                if public_key.is_empty() {
                     panic!("empty public key")
                 }
 
@@ -112,16 +112,20 @@ impl AuthTestClient {
         }
     }
 
+    pub fn inner(&self) -> &AuthClient {
+        &self.0
+    }
+
     pub fn new_for_subaccount() -> Self {
         match (CONFIG.subaccount_private_key.as_ref(), CONFIG.subaccount_public_key.as_ref(), CONFIG.subaccount.as_ref()) {
             (Some(private_key), Some(public_key), Some(subaccount)) => {
-                if private_key.len() == 0 {
+                if private_key.is_empty() {
                     panic!("empty private key")
                 }
-                if public_key.len() == 0 {
+                if public_key.is_empty() {
                     panic!("empty public key")
                 }
-                if subaccount.len() == 0 {
+                if subaccount.is_empty() {
                     panic!("empty subaccount")
                 }
                 let auth = Authenticator::new(
@@ -157,6 +161,12 @@ where
 #[derive(Clone)]
 pub struct TestClient(Client);
 
+impl Default for TestClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl TestClient {
     pub fn new() -> Self {
         Self(Client::new())
@@ -1,8 +1,10 @@
+#![cfg(feature = "client")]
+
 #[allow(dead_code)]
 mod common;
 use common::{AuthTestClient, CONFIG};
 
-use ftx_rest_client::{endpoints::fills::GetFills, Response};
+use ftx_rest_client::{data::UnixTimestamp, endpoints::fills::GetFills, Response};
 
 #[tokio::test]
 #[ignore]
@@ -25,3 +27,22 @@ async fn get_fills() {
     .deserialize()
     .unwrap();
 }
+
+#[tokio::test]
+#[ignore]
+async fn all_fills() {
+    if !CONFIG.perform_auth_api_tests {
+        return;
+    }
+
+    let end = UnixTimestamp::from_system_time();
+    let start = UnixTimestamp::new(end.get() - 30 * 24 * 60 * 60 * 1000);
+
+    AuthTestClient::new_for_main()
+        .inner()
+        .all_fills(None, start, end)
+        .await
+        .unwrap()
+        .fills()
+        .unwrap();
+}
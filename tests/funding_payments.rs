@@ -1,3 +1,5 @@
+#![cfg(feature = "client")]
+
 #[allow(dead_code)]
 mod common;
 use common::{AuthTestClient, CONFIG};
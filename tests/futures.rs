@@ -1,10 +1,14 @@
+#![cfg(feature = "client")]
+
 #[allow(dead_code)]
 mod common;
+use common::{AuthTestClient, CONFIG};
 
 use ftx_rest_client::{
     endpoints::futures::{
         GetExpiredFutures, GetFundingRates, GetFuture, GetFutureStats, GetFutures,
     },
+    error::RejectionReason,
     Response,
 };
 
@@ -56,3 +60,29 @@ async fn get_expired_futures() {
         .deserialize()
         .unwrap();
 }
+
+#[tokio::test]
+#[ignore]
+async fn future_stats_checked() {
+    if !CONFIG.perform_auth_api_tests {
+        return;
+    }
+
+    let client = AuthTestClient::new_for_main();
+
+    client
+        .inner()
+        .future_stats_checked("BTC-PERP")
+        .await
+        .unwrap()
+        .deserialize()
+        .unwrap();
+
+    let err = client
+        .inner()
+        .future_stats_checked("BTC-NOPE")
+        .await
+        .unwrap_err();
+
+    assert_eq!(err.rejection_reason(), Some(RejectionReason::UnknownFuture));
+}
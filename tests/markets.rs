@@ -1,12 +1,20 @@
+#![cfg(feature = "client")]
+
 #[allow(dead_code)]
 mod common;
 
 use ftx_rest_client::{
     data::WindowLength,
-    endpoints::markets::{BookDepth, GetCandles, GetMarket, GetMarkets, GetOrderBook, GetTrades},
-    Response,
+    endpoints::{
+        markets::{
+            BookDepth, GetCandles, GetMarket, GetMarkets, GetOrderBook, GetTrades, OrderBook,
+        },
+        raw::RawRequest,
+    },
+    Client, Response,
 };
-use std::convert::TryInto;
+use reqwest::Method;
+use std::{convert::TryInto, time::Duration};
 
 #[tokio::test]
 #[ignore]
@@ -17,6 +25,27 @@ async fn get_markets() {
         .unwrap();
 }
 
+#[tokio::test]
+#[ignore]
+async fn get_markets_raw() {
+    let request = RawRequest {
+        method: Method::GET,
+        path: "/markets".into(),
+        query: None,
+        body: None,
+        auth: false,
+    };
+
+    let response = Client::new()
+        .execute_raw(&request, Some(Duration::from_secs(10)))
+        .await
+        .unwrap();
+
+    let result = response.deserialize().unwrap();
+
+    assert!(result.get().starts_with('['));
+}
+
 #[tokio::test]
 #[ignore]
 async fn get_market() {
@@ -29,13 +58,17 @@ async fn get_market() {
 #[tokio::test]
 #[ignore]
 async fn get_orderbook() {
-    common::make_request(&GetOrderBook {
+    let book: OrderBook = common::make_request(&GetOrderBook {
         market: "BTC-PERP",
         depth: Some(BookDepth::new(20.try_into().unwrap()).unwrap()),
     })
     .await
     .deserialize()
     .unwrap();
+
+    assert!(book.bids.len() <= 20 && book.asks.len() <= 20);
+    assert!(book.bids.windows(2).all(|w| w[0].0 > w[1].0));
+    assert!(book.asks.windows(2).all(|w| w[0].0 < w[1].0));
 }
 
 #[tokio::test]
@@ -0,0 +1,83 @@
+//! Only uses what's available without the `client` feature, run with
+//! `cargo test --no-default-features --test no_client`.
+
+use bytes::Bytes;
+use ftx_rest_client::{
+    auth::{Authenticator, PrivateKey, PublicKey},
+    data::UnixTimestamp,
+    endpoints::{
+        markets::{BookDepth, GetOrderBook},
+        spot_margin::{GetBorrowRates, GetBorrowRatesResponse},
+    },
+    QueryWriter, Request, Response,
+};
+use http::Method;
+use std::{convert::TryInto, num::NonZeroU8};
+
+fn path_with_query<R, const AUTH: bool>(request: &R) -> String
+where
+    R: Request<AUTH>,
+{
+    let mut path = request.path().into_owned();
+    path.push('?');
+
+    let start = path.len();
+    request.write_query(&mut QueryWriter::new(&mut path));
+
+    if path.len() == start {
+        path.pop();
+    }
+
+    path
+}
+
+#[test]
+fn sign_and_build_path() {
+    let authenticator = Authenticator::new(
+        PublicKey::new("public"),
+        PrivateKey::new("YAGN-Np3au9igIMqIAPiJTF1zy9heo55_FNfYEru"),
+        None,
+    )
+    .unwrap();
+
+    let path = path_with_query(&GetBorrowRates);
+    assert_eq!(path, "/spot_margin/borrow_rates");
+
+    let headers = authenticator
+        .auth_headers_at(
+            UnixTimestamp::new(1617659558822),
+            &GetBorrowRates.method(),
+            &path,
+            None,
+        )
+        .unwrap();
+
+    assert_eq!(headers["FTX-KEY"], "public");
+    assert_eq!(
+        headers["FTX-SIGN"],
+        "421c580094ab840e832071c75602f1f7d1504901175589284e6ce81ff163ec0b"
+    );
+
+    let request = GetOrderBook {
+        market: "BTC-PERP",
+        depth: BookDepth::new(NonZeroU8::new(20).unwrap()),
+    };
+
+    assert_eq!(request.method(), Method::GET);
+    assert_eq!(
+        path_with_query(&request),
+        "/markets/BTC-PERP/orderbook?depth=20"
+    );
+}
+
+#[test]
+fn deserialize_response() {
+    let json =
+        r#"{"success":true,"result":[{"coin":"BTC","estimate":1.45e-06,"previous":1.44e-06}]}"#;
+
+    let response: GetBorrowRatesResponse = Bytes::from(json).into();
+    let rates = response.deserialize().unwrap();
+
+    assert_eq!(rates[0].coin, "BTC");
+    assert_eq!(rates[0].previous, "0.00000144".try_into().unwrap());
+}
@@ -1,16 +1,23 @@
+#![cfg(feature = "client")]
+
 #[allow(dead_code)]
 mod common;
 use common::{AuthTestClient, CONFIG};
 
 use ftx_rest_client::{
-    data::Side,
-    endpoints::orders::{
-        CancelAllOrders, CancelOrder, EditOrder, EditOrderOpts, GetOpenOrders, GetOrderStatus,
-        OrderId, PlaceOrder,
+    data::{PositiveDecimal, Side},
+    endpoints::{
+        markets::GetMarket,
+        orders::{
+            CancelAllOrders, CancelOrder, EditOrder, EditOrderOpts, GetOpenOrders, GetOrderStatus,
+            OrderId, PlaceOrder,
+        },
     },
     Response,
 };
+use rust_decimal::Decimal;
 use serial_test::serial;
+use std::{num::NonZeroU32, time::Duration};
 use uuid::Uuid;
 
 #[tokio::test]
@@ -27,7 +34,7 @@ async fn place_limit_order_then_check_status() {
     let order_id = common::make_auth_request(
         &client,
         &PlaceOrder {
-            market: &market,
+            market,
             price: Some("1".parse().unwrap()),
             side: Side::Buy,
             size: "0.001".parse().unwrap(),
@@ -43,7 +50,7 @@ async fn place_limit_order_then_check_status() {
     let has_id = common::make_auth_request(
         &client,
         &GetOpenOrders {
-            market: Some(&market),
+            market: Some(market),
         },
     )
     .await
@@ -77,7 +84,7 @@ async fn place_limit_order_then_check_status() {
     let has_id = common::make_auth_request(
         &client,
         &GetOpenOrders {
-            market: Some(&market),
+            market: Some(market),
         },
     )
     .await
@@ -105,7 +112,7 @@ async fn place_limit_order_then_check_status_with_client_id() {
     common::make_auth_request(
         &client,
         &PlaceOrder {
-            market: &market,
+            market,
             price: Some("1".parse().unwrap()),
             side: Side::Buy,
             size: "0.001".parse().unwrap(),
@@ -115,13 +122,12 @@ async fn place_limit_order_then_check_status_with_client_id() {
     )
     .await
     .deserialize()
-    .unwrap()
-    .id;
+    .unwrap();
 
     let has_id = common::make_auth_request(
         &client,
         &GetOpenOrders {
-            market: Some(&market),
+            market: Some(market),
         },
     )
     .await
@@ -155,7 +161,7 @@ async fn place_limit_order_then_check_status_with_client_id() {
     let has_id = common::make_auth_request(
         &client,
         &GetOpenOrders {
-            market: Some(&market),
+            market: Some(market),
         },
     )
     .await
@@ -184,7 +190,7 @@ async fn place_limit_order_then_edit() {
     let order_id = common::make_auth_request(
         &client,
         &PlaceOrder {
-            market: &market,
+            market,
             price: Some("1".parse().unwrap()),
             side: Side::Buy,
             size: "0.001".parse().unwrap(),
@@ -200,7 +206,7 @@ async fn place_limit_order_then_edit() {
     let has_id = common::make_auth_request(
         &client,
         &GetOpenOrders {
-            market: Some(&market),
+            market: Some(market),
         },
     )
     .await
@@ -244,7 +250,7 @@ async fn place_limit_order_then_edit() {
     let has_id = common::make_auth_request(
         &client,
         &GetOpenOrders {
-            market: Some(&market),
+            market: Some(market),
         },
     )
     .await
@@ -276,7 +282,7 @@ async fn place_limit_order_then_edit_with_client_id() {
     common::make_auth_request(
         &client,
         &PlaceOrder {
-            market: &market,
+            market,
             price: Some("1".parse().unwrap()),
             side: Side::Buy,
             size: "0.001".parse().unwrap(),
@@ -291,7 +297,7 @@ async fn place_limit_order_then_edit_with_client_id() {
     let has_id = common::make_auth_request(
         &client,
         &GetOpenOrders {
-            market: Some(&market),
+            market: Some(market),
         },
     )
     .await
@@ -334,7 +340,7 @@ async fn place_limit_order_then_edit_with_client_id() {
     let has_id = common::make_auth_request(
         &client,
         &GetOpenOrders {
-            market: Some(&market),
+            market: Some(market),
         },
     )
     .await
@@ -424,3 +430,150 @@ async fn cancel_all_orders() {
     assert!(!has_btc_order_id);
     assert!(!has_eth_order_id);
 }
+
+#[tokio::test]
+#[serial]
+#[ignore]
+async fn wait_for_cancelled_order() {
+    if !CONFIG.perform_auth_api_tests || !CONFIG.perform_order_placement_tests {
+        return;
+    }
+
+    let client = AuthTestClient::new_for_subaccount();
+
+    let order_id = common::make_auth_request(
+        &client,
+        &PlaceOrder {
+            market: "ETH-PERP",
+            price: Some("1".parse().unwrap()),
+            side: Side::Buy,
+            size: "0.001".parse().unwrap(),
+            client_id: None,
+            opts: None,
+        },
+    )
+    .await
+    .deserialize()
+    .unwrap()
+    .id;
+
+    common::make_auth_request(
+        &client,
+        &CancelOrder {
+            order_id: OrderId::Exchange(order_id),
+        },
+    )
+    .await
+    .deserialize()
+    .unwrap();
+
+    let order = client
+        .inner()
+        .wait_for_order(
+            OrderId::Exchange(order_id),
+            Duration::from_millis(200),
+            Duration::from_secs(10),
+        )
+        .await
+        .unwrap();
+
+    assert!(order.deserialize().unwrap().status.is_terminal());
+}
+
+#[tokio::test]
+#[serial]
+#[ignore]
+async fn cancel_orders_older_than() {
+    if !CONFIG.perform_auth_api_tests || !CONFIG.perform_order_placement_tests {
+        return;
+    }
+
+    let client = AuthTestClient::new_for_subaccount();
+
+    let order_id = common::make_auth_request(
+        &client,
+        &PlaceOrder {
+            market: "ETH-PERP",
+            price: Some("1".parse().unwrap()),
+            side: Side::Buy,
+            size: "0.001".parse().unwrap(),
+            client_id: None,
+            opts: None,
+        },
+    )
+    .await
+    .deserialize()
+    .unwrap()
+    .id;
+
+    // The order is too recent to be cancelled.
+    let cancelled = client
+        .inner()
+        .cancel_orders_older_than(Some("ETH-PERP"), Duration::from_secs(60))
+        .await
+        .unwrap();
+
+    assert!(!cancelled.contains(&order_id));
+
+    tokio::time::sleep(Duration::from_secs(2)).await;
+
+    let cancelled = client
+        .inner()
+        .cancel_orders_older_than(Some("ETH-PERP"), Duration::from_secs(1))
+        .await
+        .unwrap();
+
+    assert!(cancelled.contains(&order_id));
+}
+
+#[tokio::test]
+#[serial]
+#[ignore]
+async fn place_post_only_order_with_reprice() {
+    if !CONFIG.perform_auth_api_tests || !CONFIG.perform_order_placement_tests {
+        return;
+    }
+
+    let market = "ETH-PERP";
+    let client = AuthTestClient::new_for_subaccount();
+
+    let ask = common::make_request(&GetMarket { market })
+        .await
+        .deserialize()
+        .unwrap()
+        .ask
+        .unwrap();
+
+    // Price through the ask so the first placement would take.
+    let order = PlaceOrder {
+        market,
+        side: Side::Buy,
+        price: PositiveDecimal::new(ask * Decimal::new(11, 1)),
+        size: "0.001".parse().unwrap(),
+        client_id: None,
+        opts: None,
+    };
+
+    let order_id = client
+        .inner()
+        .place_post_only_with_reprice(
+            order,
+            NonZeroU32::new(3).unwrap(),
+            Some(Duration::from_secs(10)),
+        )
+        .await
+        .unwrap()
+        .deserialize()
+        .unwrap()
+        .id;
+
+    common::make_auth_request(
+        &client,
+        &CancelOrder {
+            order_id: OrderId::Exchange(order_id),
+        },
+    )
+    .await
+    .deserialize()
+    .unwrap();
+}
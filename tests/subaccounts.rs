@@ -1,3 +1,5 @@
+#![cfg(feature = "client")]
+
 #[allow(dead_code)]
 mod common;
 use common::{AuthTestClient, CONFIG};
@@ -10,7 +12,6 @@ use ftx_rest_client::{
     Response,
 };
 use rust_decimal::Decimal;
-use std::convert::TryInto;
 use tokio::task;
 
 #[tokio::test]
@@ -43,6 +44,24 @@ async fn get_subaccount_balances() {
         .unwrap();
 }
 
+#[tokio::test]
+#[ignore]
+async fn all_subaccount_balances() {
+    if !CONFIG.perform_auth_api_tests {
+        return;
+    }
+
+    let balances = AuthTestClient::new_for_main()
+        .inner()
+        .all_subaccount_balances()
+        .await
+        .unwrap();
+
+    for response in balances.values() {
+        response.as_ref().unwrap().deserialize().unwrap();
+    }
+}
+
 #[tokio::test]
 #[ignore]
 async fn create_subaccount_then_change_its_name_then_delete_it() {
@@ -134,7 +153,7 @@ async fn try_transfer_one_usd_from_main_to_subaccount_then_back() {
                 &client,
                 &TransferBetweenSubaccounts {
                     coin: "USD",
-                    size: Decimal::ONE.try_into().unwrap(),
+                    size: Decimal::ONE,
                     source: None,
                     destination: Some(NICKNAME),
                 },
@@ -147,7 +166,7 @@ async fn try_transfer_one_usd_from_main_to_subaccount_then_back() {
                 &client,
                 &TransferBetweenSubaccounts {
                     coin: "USD",
-                    size: Decimal::ONE.try_into().unwrap(),
+                    size: Decimal::ONE,
                     source: Some(NICKNAME),
                     destination: None,
                 },
@@ -166,3 +185,26 @@ async fn try_transfer_one_usd_from_main_to_subaccount_then_back() {
         res.unwrap()
     }
 }
+
+#[tokio::test]
+#[ignore]
+async fn transfer_checked_rejects_overdraw() {
+    if !CONFIG.perform_auth_api_tests {
+        return;
+    }
+
+    // The check fails before anything is sent, so the destination doesn't
+    // need to exist.
+    let err = AuthTestClient::new_for_main()
+        .inner()
+        .transfer_checked(&TransferBetweenSubaccounts {
+            coin: "USD",
+            size: Decimal::new(1_000_000_000_000, 0),
+            source: None,
+            destination: Some("ftx_client_test"),
+        })
+        .await
+        .unwrap_err();
+
+    assert_eq!(err.to_string(), "insufficient balance");
+}
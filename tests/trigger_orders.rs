@@ -0,0 +1,114 @@
+#![cfg(feature = "client")]
+
+#[allow(dead_code)]
+mod common;
+use common::{AuthTestClient, CONFIG};
+
+use ftx_rest_client::{
+    data::Side,
+    endpoints::trigger_orders::{
+        CancelTriggerOrder, GetOpenTriggerOrders, GetTriggerOrderHistory, GetTriggerOrderTriggers,
+        ModifyTriggerOrder, ModifyTriggerOrderOpts, PlaceTriggerOrder, TriggerOrderType,
+    },
+    Response,
+};
+use serial_test::serial;
+
+#[tokio::test]
+#[ignore]
+async fn get_trigger_order_history() {
+    if !CONFIG.perform_auth_api_tests {
+        return;
+    }
+
+    common::make_auth_request(
+        &AuthTestClient::new_for_main(),
+        &GetTriggerOrderHistory {
+            market: None,
+            side: None,
+            r#type: None,
+            order_type: None,
+            start_time: None,
+            end_time: None,
+        },
+    )
+    .await
+    .deserialize()
+    .unwrap();
+}
+
+#[tokio::test]
+#[serial]
+#[ignore]
+async fn place_stop_then_modify_and_cancel() {
+    if !CONFIG.perform_auth_api_tests || !CONFIG.perform_order_placement_tests {
+        return;
+    }
+
+    let market = "ETH-PERP";
+    let client = AuthTestClient::new_for_subaccount();
+
+    // A buy stop far above the market so it's never triggered.
+    let order_id = common::make_auth_request(
+        &client,
+        &PlaceTriggerOrder {
+            market,
+            side: Side::Buy,
+            size: "0.001".parse().unwrap(),
+            r#type: TriggerOrderType::Stop,
+            trigger_price: Some("1000000".parse().unwrap()),
+            order_price: None,
+            trail_value: None,
+            reduce_only: None,
+            retry_until_filled: None,
+        },
+    )
+    .await
+    .deserialize()
+    .unwrap()
+    .id;
+
+    let has_id = common::make_auth_request(
+        &client,
+        &GetOpenTriggerOrders {
+            market: Some(market),
+            r#type: Some(TriggerOrderType::Stop),
+        },
+    )
+    .await
+    .deserialize()
+    .unwrap()
+    .into_iter()
+    .any(|o| o.id == order_id);
+
+    assert!(has_id);
+
+    let triggers = common::make_auth_request(&client, &GetTriggerOrderTriggers { order_id })
+        .await
+        .deserialize()
+        .unwrap()
+        .len();
+
+    assert_eq!(triggers, 0);
+
+    let order_id = common::make_auth_request(
+        &client,
+        &ModifyTriggerOrder {
+            order_id,
+            opts: ModifyTriggerOrderOpts {
+                size: Some("0.001".parse().unwrap()),
+                trigger_price: Some("2000000".parse().unwrap()),
+                ..Default::default()
+            },
+        },
+    )
+    .await
+    .deserialize()
+    .unwrap()
+    .id;
+
+    common::make_auth_request(&client, &CancelTriggerOrder { order_id })
+        .await
+        .deserialize()
+        .unwrap();
+}
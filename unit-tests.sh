@@ -1,3 +1,6 @@
 #!/bin/bash
 
 cargo test --features deny-unknown-fields --lib
+cargo test --features chrono --lib
+cargo test --features compression --lib
+cargo test --no-default-features